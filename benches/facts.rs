@@ -0,0 +1,145 @@
+use arbitrary::Arbitrary;
+use contrafact::{facts::*, *};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+
+#[derive(Clone, Debug, PartialEq, Arbitrary)]
+struct S {
+    x: u32,
+    y: u32,
+    name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Arbitrary)]
+enum E {
+    X(u32),
+    Y(S),
+}
+
+fn e_y(e: &mut E) -> Option<&mut S> {
+    match e {
+        E::Y(s) => Some(s),
+        _ => None,
+    }
+}
+
+fn s_fact<'a>() -> impl Fact<'a, S> {
+    facts![
+        lens1("S::x", |s: &mut S| &mut s.x, eq(1)),
+        lens1("S::y", |s: &mut S| &mut s.y, in_range("small", 0..100)),
+    ]
+}
+
+/// A fact about one item of an array
+fn item<'a>(i: usize) -> impl Fact<'a, [u32; 16]> {
+    lens1(
+        format!("[{}]", i),
+        move |a: &mut [u32; 16]| &mut a[i],
+        in_range("small", 0..100),
+    )
+}
+
+/// A `facts!` tree which is 16 `and`s deep
+fn deep<'a>() -> impl Fact<'a, [u32; 16]> {
+    facts![
+        item(0),
+        item(1),
+        item(2),
+        item(3),
+        item(4),
+        item(5),
+        item(6),
+        item(7),
+        item(8),
+        item(9),
+        item(10),
+        item(11),
+        item(12),
+        item(13),
+        item(14),
+        item(15),
+    ]
+}
+
+fn bench_eq(c: &mut Criterion) {
+    let fact = eq(42u32);
+    c.bench_function("eq/check", |b| {
+        b.iter(|| fact.clone().check(black_box(&42)))
+    });
+    c.bench_function("eq/check_fail", |b| {
+        b.iter(|| fact.clone().check(black_box(&41)))
+    });
+}
+
+fn bench_lens(c: &mut Criterion) {
+    let fact = s_fact();
+    let s = S {
+        x: 1,
+        y: 50,
+        name: "a fairly long name which is expensive to clone".to_string(),
+    };
+    c.bench_function("lens1/check", |b| {
+        b.iter(|| fact.clone().check(black_box(&s)))
+    });
+    c.bench_function("lens1/build", |b| {
+        let mut g = utils::random_generator().with_fallback();
+        b.iter(|| s_fact().build(&mut g))
+    });
+}
+
+fn bench_vec(c: &mut Criterion) {
+    let fact = vec(in_range("small", 0..100u32));
+    let items: Vec<u32> = (0..10_000).map(|i| i % 100).collect();
+    c.bench_function("vec/check_10k", |b| {
+        b.iter(|| fact.clone().check(black_box(&items)))
+    });
+    c.bench_function("vec/satisfy_10k", |b| {
+        let mut g = utils::random_generator().with_fallback();
+        b.iter_batched(
+            || items.iter().map(|i| i + 50).collect::<Vec<_>>(),
+            |items| fact.clone().satisfy(&mut g, items).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn bench_prism(c: &mut Criterion) {
+    let fact = vec(prism("E::Y", e_y, s_fact()));
+    let items: Vec<E> = (0..1000)
+        .map(|i| {
+            if i % 2 == 0 {
+                E::X(i)
+            } else {
+                E::Y(S {
+                    x: 1,
+                    y: i % 100,
+                    name: "name".to_string(),
+                })
+            }
+        })
+        .collect();
+    c.bench_function("prism/check_1k", |b| {
+        b.iter(|| fact.clone().check(black_box(&items)))
+    });
+}
+
+fn bench_deep(c: &mut Criterion) {
+    let fact = deep();
+    let a = [7u32; 16];
+    c.bench_function("deep/check", |b| {
+        b.iter(|| fact.clone().check(black_box(&a)))
+    });
+    c.bench_function("deep/build", |b| {
+        let mut g = utils::random_generator().with_fallback();
+        b.iter(|| deep().build(&mut g))
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_eq,
+    bench_lens,
+    bench_vec,
+    bench_prism,
+    bench_deep
+);
+criterion_main!(benches);
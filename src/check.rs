@@ -5,8 +5,6 @@ use crate::*;
 ///
 /// There are two levels of "error" here: the failures due to data which does not
 /// meet the constraints, and also internal errors due to a poorly written Fact.
-//
-// TODO: add ability to abort, so that further checks will not occur
 #[derive(Debug, Clone, PartialEq, Eq, derive_more::From)]
 #[must_use = "Check should be used with either `.unwrap()` or `.result()`"]
 pub enum Check {
@@ -14,6 +12,12 @@ pub enum Check {
     /// An empty list of failures means the data is valid per this check.
     Failures(Vec<Failure>),
 
+    /// The check was cut short because a precondition failed, so that dependent
+    /// facts didn't report follow-on failures. The last failure is the precondition's.
+    /// See [`Generator::precondition`].
+    #[from(ignore)]
+    Abort(Vec<Failure>),
+
     /// There was a problem actually running the check: there is a bug in a Fact
     /// or Generator.
     //
@@ -32,6 +36,7 @@ impl Check {
     {
         match self {
             Self::Failures(failures) => Self::Failures(failures.into_iter().map(f).collect()),
+            Self::Abort(failures) => Self::Abort(failures.into_iter().map(f).collect()),
             e => e,
         }
     }
@@ -48,10 +53,85 @@ impl Check {
                     };
                 }
             }
+            Self::Abort(failures) => panic!("Check aborted: {:#?}", failures),
             Self::Error(err) => panic!("Internal contrafact error. Check your Facts! {:?}", err),
         }
     }
 
+    /// Prefix each failure with some context, e.g. which part of the data it's about.
+    ///
+    /// ```
+    /// use contrafact::*;
+    /// assert_eq!(Check::fail("too big").with_context("x"), Check::fail("x: too big"));
+    /// ```
+    pub fn with_context(self, context: impl std::fmt::Display) -> Self {
+        self.map(|failure| format!("{}: {}", context, failure))
+    }
+
+    /// Combine several checks of independent parts of the data into one,
+    /// keeping every failure in order.
+    ///
+    /// The result is an Abort if any of them was, and an Error if any of them was.
+    ///
+    /// ```
+    /// use contrafact::*;
+    /// let check = Check::merge([Check::fail("a"), Check::pass(), Check::fail("b")]);
+    /// assert_eq!(check, Check::Failures(vec!["a".to_string(), "b".to_string()]));
+    /// ```
+    pub fn merge(checks: impl IntoIterator<Item = Check>) -> Self {
+        let mut failures = vec![];
+        let mut aborted = false;
+        for check in checks {
+            match check {
+                Self::Failures(more) => failures.extend(more),
+                Self::Abort(more) => {
+                    failures.extend(more);
+                    aborted = true;
+                }
+                error => return error,
+            }
+        }
+        if aborted {
+            Self::Abort(failures)
+        } else {
+            Self::Failures(failures)
+        }
+    }
+
+    /// Combine with a check of another independent part of the data.
+    /// See [`Check::merge`].
+    pub fn and(self, other: Check) -> Self {
+        Self::merge([self, other])
+    }
+
+    /// Combine with a check of the next part of the data, which only runs
+    /// if this one wasn't cut short by an Abort or an Error.
+    /// This is how a check by [`Generator::accumulate`] carries on after a failure.
+    ///
+    /// ```
+    /// use contrafact::*;
+    /// let check = Check::fail("a").and_then(|| Check::fail("b"));
+    /// assert_eq!(check.len(), 2);
+    /// let check = Check::Abort(vec!["a".to_string()]).and_then(|| unreachable!());
+    /// assert_eq!(check.len(), 1);
+    /// ```
+    pub fn and_then(self, next: impl FnOnce() -> Check) -> Self {
+        match self {
+            Self::Failures(mut failures) => match next() {
+                Self::Failures(more) => {
+                    failures.extend(more);
+                    Self::Failures(failures)
+                }
+                Self::Abort(more) => {
+                    failures.extend(more);
+                    Self::Abort(failures)
+                }
+                error => error,
+            },
+            cut_short => cut_short,
+        }
+    }
+
     /// There are no errors.
     pub fn is_ok(&self) -> bool {
         matches!(self, Self::Failures(failures) if failures.is_empty())
@@ -62,10 +142,33 @@ impl Check {
         !self.is_ok()
     }
 
+    /// The number of failures. An Error has none.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Failures(failures) | Self::Abort(failures) => failures.len(),
+            Self::Error(_) => 0,
+        }
+    }
+
+    /// There are no failures, and no Error.
+    ///
+    /// ```
+    /// use contrafact::*;
+    /// assert!(Check::pass().is_empty());
+    /// assert!(!Check::fail("a").is_empty());
+    /// assert!(!Check::Error("oops".to_string()).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::Failures(failures) | Self::Abort(failures) => failures.is_empty(),
+            Self::Error(_) => false,
+        }
+    }
+
     /// Get errors if they exist
     pub fn failures(&self) -> Result<&[Failure], ContrafactError> {
         match self {
-            Self::Failures(failures) => Ok(failures.as_ref()),
+            Self::Failures(failures) | Self::Abort(failures) => Ok(failures.as_ref()),
             Self::Error(err) => Err(err.clone().into()),
         }
     }
@@ -87,10 +190,35 @@ impl Check {
                     Ok(Err(failures))
                 }
             }
+            Self::Abort(failures) => Ok(Err(failures)),
             Self::Error(err) => Err(err.into()),
         }
     }
 
+    /// Convert to a Result with a single error type, so that a check can be
+    /// propagated with `?`
+    ///
+    /// ```
+    /// use contrafact::*;
+    ///
+    /// fn check_both(a: Check, b: Check) -> Result<(), CheckError> {
+    ///     a.into_result()?;
+    ///     b.into_result()
+    /// }
+    ///
+    /// assert_eq!(check_both(Check::pass(), Check::pass()), Ok(()));
+    /// assert_eq!(
+    ///     check_both(Check::pass(), Check::fail("b")),
+    ///     Err(CheckError::Failures(vec!["b".to_string()]))
+    /// );
+    /// ```
+    pub fn into_result(self) -> Result<(), CheckError> {
+        match self.result()? {
+            Ok(()) => Ok(()),
+            Err(failures) => Err(CheckError::Failures(failures)),
+        }
+    }
+
     /// If Failures, return all failures joined together in a single string
     pub fn result_joined(self) -> ContrafactResult<std::result::Result<(), String>> {
         self.result().map(|r| r.map_err(|es| es.join(";")))
@@ -103,6 +231,7 @@ impl Check {
     /// assert_eq!(Check::check(true, "message"), Check::pass());
     /// assert_eq!(Check::check(false, "message"), Check::fail("message"));
     /// ```
+    #[allow(clippy::self_named_constructors)]
     pub fn check<S: ToString>(ok: bool, err: S) -> Self {
         if ok {
             Self::pass()
@@ -122,7 +251,10 @@ impl Check {
         match res {
             Ok(_) => Self::pass(),
             Err(MutationError::Check(err)) => Self::fail(err),
+            Err(MutationError::Abort(err)) => Self::Abort(vec![err]),
+            #[cfg(feature = "arbitrary")]
             Err(MutationError::Arbitrary(err)) => Self::Error(err.to_string()),
+            Err(MutationError::Exhausted) => Self::Error("Ran out of entropy".to_string()),
             Err(MutationError::Internal(err)) => Self::Error(format!("{:?}", err)),
             Err(MutationError::User(err)) => Self::Error(format!("{:?}", err)),
         }
@@ -154,3 +286,10 @@ impl Check {
         Self::Failures(vec![error.to_string()])
     }
 }
+
+impl FromIterator<Check> for Check {
+    /// Combine checks with [`Check::merge`]
+    fn from_iter<I: IntoIterator<Item = Check>>(checks: I) -> Self {
+        Self::merge(checks)
+    }
+}
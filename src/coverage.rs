@@ -0,0 +1,277 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use crate::*;
+
+/// When building with a [`Coverage`] which has uncovered branches, draw up to
+/// this many initial values, looking for one which reaches an uncovered branch
+#[cfg(feature = "arbitrary")]
+const COVERAGE_CANDIDATES: usize = 16;
+
+/// A record of which branches of each `prism` and `or` were reached by the
+/// values built by a Generator.
+///
+/// Attach it to a Generator with [`Generator::with_coverage`]. Each value built
+/// with [`Fact::build`] is then checked to see whether each `prism` matched
+/// (`Some`) or not (`None`), and which side of each `or` was met. Later builds
+/// try several initial values and pick one which reaches a branch which hasn't
+/// been reached yet, and `or` prefers to mutate towards its less covered side,
+/// so that rare enum variants aren't left untested.
+///
+/// ```
+/// # #[cfg(feature = "utils")] {
+/// use contrafact::{*, facts::*};
+///
+/// let coverage = Coverage::new();
+/// let mut g = utils::random_generator().with_coverage(coverage.clone());
+///
+/// let fact = vec_of_length(3, or(eq(1u8), eq(2)));
+/// for _ in 0..5 {
+///     fact.clone().build(&mut g);
+/// }
+///
+/// let summary = coverage.summary();
+/// assert!(summary.uncovered().is_empty());
+/// println!("{}", summary);
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Coverage(Arc<Mutex<BTreeMap<String, CoveragePoint>>>);
+
+/// The number of times each branch of one `prism` or `or` was reached
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CoveragePoint {
+    /// "prism" or "or"
+    pub kind: String,
+    /// The name of each branch, with the number of times it was reached
+    pub branches: Vec<(&'static str, usize)>,
+}
+
+/// A snapshot of a [`Coverage`], keyed by the path to each `prism` and `or`
+/// in the fact tree. Vec indices are collapsed to `[*]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CoverageSummary {
+    /// Every point which has been reached at least once
+    pub points: BTreeMap<String, CoveragePoint>,
+}
+
+impl Coverage {
+    /// Create an empty record
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// What has been covered so far
+    pub fn summary(&self) -> CoverageSummary {
+        CoverageSummary {
+            points: self.0.lock().unwrap().clone(),
+        }
+    }
+
+    /// Count a hit on one branch of a point
+    pub(crate) fn hit(&self, key: String, kind: &str, branches: &[&'static str], branch: usize) {
+        let mut points = self.0.lock().unwrap();
+        let point = points.entry(key).or_insert_with(|| CoveragePoint {
+            kind: kind.to_string(),
+            branches: branches.iter().map(|b| (*b, 0)).collect(),
+        });
+        point.branches[branch].1 += 1;
+    }
+
+    /// The branch of a point with the fewest hits, if any branch has fewer than the others
+    pub(crate) fn least_hit(&self, key: &str) -> Option<usize> {
+        let points = self.0.lock().unwrap();
+        let hits: Vec<usize> = points.get(key)?.branches.iter().map(|b| b.1).collect();
+        let min = *hits.iter().min()?;
+        if hits.iter().all(|h| *h == min) {
+            return None;
+        }
+        hits.iter().position(|h| *h == min)
+    }
+
+    /// The number of branches hit by `other` which haven't been hit here
+    #[cfg(feature = "arbitrary")]
+    fn novelty(&self, other: &Coverage) -> usize {
+        let points = self.0.lock().unwrap();
+        let others = other.0.lock().unwrap();
+        others
+            .iter()
+            .map(|(key, point)| {
+                point
+                    .branches
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, (_, hits))| {
+                        *hits > 0 && points.get(key).is_none_or(|p| p.branches[*i].1 == 0)
+                    })
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Record which branches a value reaches, by checking it against the fact
+    pub(crate) fn record<'a, T, F>(&self, fact: &F, t: &T)
+    where
+        T: Target<'a>,
+        F: Fact<'a, T>,
+    {
+        let mut checker = Generator::checker().with_coverage(self.clone());
+        // Only the branches taken matter, not whether the check passes
+        let _ = fact.clone().mutate(&mut checker, t.clone());
+    }
+}
+
+impl CoverageSummary {
+    /// The key and name of every branch which was never reached, for points
+    /// where some other branch was reached
+    pub fn uncovered(&self) -> Vec<(String, &'static str)> {
+        self.points
+            .iter()
+            .flat_map(|(key, point)| {
+                point
+                    .branches
+                    .iter()
+                    .filter(|(_, hits)| *hits == 0)
+                    .map(move |(name, _)| (key.clone(), *name))
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for CoverageSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (key, point) in self.points.iter() {
+            let branches: Vec<String> = point
+                .branches
+                .iter()
+                .map(|(name, hits)| format!("{} {}", name, hits))
+                .collect();
+            let mark = if point.branches.iter().any(|b| b.1 == 0) {
+                "  (uncovered)"
+            } else {
+                ""
+            };
+            writeln!(f, "{} {}: {}{}", point.kind, key, branches.join(", "), mark)?;
+        }
+        Ok(())
+    }
+}
+
+/// Draw an initial value to build from. If the Generator has a Coverage with
+/// uncovered branches, prefer a value which reaches one of them.
+#[cfg(feature = "arbitrary")]
+pub(crate) fn steer<'a, T, F>(fact: &F, g: &mut Generator<'_>) -> Mutation<T>
+where
+    T: Target<'a> + for<'u> arbitrary::Arbitrary<'u>,
+    F: Fact<'a, T>,
+{
+    let coverage = match g.coverage() {
+        Some(coverage) if !coverage.summary().uncovered().is_empty() => coverage.clone(),
+        _ => return g.arbitrary(|| "build"),
+    };
+    let mut t = g.arbitrary(|| "build")?;
+    for _ in 1..COVERAGE_CANDIDATES {
+        let probe = Coverage::new();
+        probe.record(fact, &t);
+        if coverage.novelty(&probe) > 0 {
+            break;
+        }
+        t = g.arbitrary(|| "build")?;
+    }
+    Ok(t)
+}
+
+#[cfg(test)]
+#[cfg(feature = "utils")]
+mod tests {
+    use super::*;
+    use crate::facts::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum E {
+        Common(u8),
+        Rare(u8),
+    }
+
+    /// Only about 1 in 64 arbitrary values is Rare
+    impl<'a> arbitrary::Arbitrary<'a> for E {
+        fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+            let x = u8::arbitrary(u)?;
+            if x < 4 {
+                Ok(E::Rare(x))
+            } else {
+                Ok(E::Common(x))
+            }
+        }
+    }
+
+    fn rare(e: &mut E) -> Option<&mut u8> {
+        match e {
+            E::Rare(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_coverage_steers_towards_rare_variants() {
+        let coverage = Coverage::new();
+        let mut g = Generator::from(&[][..])
+            .with_fallback()
+            .with_coverage(coverage.clone());
+        let fact = prism("E::Rare", rare, eq(1));
+
+        let built: Vec<E> = (0..20).map(|_| fact.clone().build(&mut g)).collect();
+        assert!(built.contains(&E::Rare(1)));
+
+        let summary = coverage.summary();
+        assert!(summary.uncovered().is_empty(), "{}", summary);
+        let point = &summary.points["E::Rare"];
+        assert_eq!(point.kind, "prism");
+        assert_eq!(point.branches.iter().map(|b| b.1).sum::<usize>(), 20);
+    }
+
+    #[test]
+    fn test_coverage_inside_or_and_when() {
+        let coverage = Coverage::new();
+        let fact = or(prism("E::Rare", rare, eq(1)), eq(E::Common(5)));
+        coverage.record(&fact, &E::Rare(1));
+        coverage.record(&fact, &E::Common(5));
+
+        let summary = coverage.summary();
+        let hits: Vec<_> = summary.points["E::Rare"]
+            .branches
+            .iter()
+            .map(|b| b.1)
+            .collect();
+        assert_eq!(hits, vec![1, 1]);
+
+        let coverage = Coverage::new();
+        let fact = when(
+            prism("E::Rare", rare, eq(1)),
+            eq(E::Rare(1)),
+            eq(E::Common(5)),
+        );
+        coverage.record(&fact, &E::Rare(1));
+
+        let summary = coverage.summary();
+        assert_eq!(summary.uncovered(), vec![("E::Rare".to_string(), "None")]);
+    }
+
+    #[test]
+    fn test_coverage_summary() {
+        let coverage = Coverage::new();
+        let fact = vec(or(eq(1u8), eq(2)));
+        coverage.record(&fact, &vec![1, 1, 1]);
+
+        let summary = coverage.summary();
+        assert_eq!(
+            summary.uncovered(),
+            vec![("[*]/or(eq(1), eq(2))".to_string(), "right")]
+        );
+        assert_eq!(
+            summary.to_string(),
+            "or [*]/or(eq(1), eq(2)): left 3, right 0  (uncovered)\n"
+        );
+        assert_eq!(coverage.least_hit("[*]/or(eq(1), eq(2))"), Some(1));
+    }
+}
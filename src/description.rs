@@ -0,0 +1,320 @@
+use std::fmt::Write;
+use std::ops::Bound;
+
+/// A tree describing a Fact and the facts it is made of, as returned by
+/// [`Fact::describe`](crate::Fact::describe).
+///
+/// This is useful for seeing what a large composite fact actually enforces.
+///
+/// ```
+/// use contrafact::{*, facts::*};
+///
+/// #[derive(Debug, Clone, PartialEq)]
+/// struct S {
+///     x: u32,
+///     y: Option<u32>,
+/// }
+///
+/// let fact = facts![
+///     lens1("S::x", |s: &mut S| &mut s.x, eq(1)),
+///     prism("S::y", |s: &mut S| s.y.as_mut(), eq(2)),
+/// ];
+///
+/// assert_eq!(
+///     fact.describe().to_text(),
+///     "and\n  lens: S::x\n    fact: eq(1)\n  prism: S::y\n    fact: eq(2)\n"
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Description {
+    /// What sort of fact this is, e.g. "lens", "prism", "and", "or", "vec"
+    pub kind: String,
+    /// The label of the fact
+    pub label: String,
+    /// Descriptions of the facts which this fact is made of
+    pub children: Vec<Description>,
+    /// For simple facts like `eq`, the constraint they enforce,
+    /// so that it can be compared with others by [`Description::contradictions`]
+    pub constraint: Option<Constraint>,
+}
+
+impl Description {
+    /// A description with no children
+    pub fn new(kind: impl ToString, label: impl ToString) -> Self {
+        Self {
+            kind: kind.to_string(),
+            label: label.to_string(),
+            children: vec![],
+            constraint: None,
+        }
+    }
+
+    /// Attach a constraint to this description
+    pub fn with_constraint(mut self, constraint: Constraint) -> Self {
+        self.constraint = Some(constraint);
+        self
+    }
+
+    /// Add children to this description
+    pub fn with_children(mut self, children: impl IntoIterator<Item = Description>) -> Self {
+        self.children.extend(children);
+        self
+    }
+
+    /// The kind and label of this node, without repeating the label if it's the same as the kind
+    fn heading(&self) -> String {
+        if self.kind == self.label {
+            self.kind.clone()
+        } else {
+            format!("{}: {}", self.kind, self.label)
+        }
+    }
+
+    /// Render as indented text, one fact per line
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        self.write_text(&mut out, 0);
+        out
+    }
+
+    fn write_text(&self, out: &mut String, depth: usize) {
+        writeln!(out, "{}{}", "  ".repeat(depth), self.heading()).unwrap();
+        for child in self.children.iter() {
+            child.write_text(out, depth + 1);
+        }
+    }
+
+    /// Render as a Graphviz DOT digraph
+    pub fn to_dot(&self) -> String {
+        let mut out = "digraph fact {\n".to_string();
+        self.write_dot(&mut out, &mut 0);
+        out.push_str("}\n");
+        out
+    }
+
+    /// Write this node and its children, returning this node's id
+    fn write_dot(&self, out: &mut String, next_id: &mut usize) -> usize {
+        let id = *next_id;
+        *next_id += 1;
+        let heading = self.heading().replace('\\', "\\\\").replace('"', "\\\"");
+        writeln!(out, "  n{} [label=\"{}\"];", id, heading).unwrap();
+        for child in self.children.iter() {
+            let child_id = child.write_dot(out, next_id);
+            writeln!(out, "  n{} -> n{};", id, child_id).unwrap();
+        }
+        id
+    }
+}
+
+impl Description {
+    /// Find pairs of simple facts (`eq`, `in_range`, `in_slice`) which apply to
+    /// the same part of the data and obviously can't both be met, like
+    /// `facts![eq(1), eq(2)]`.
+    ///
+    /// This is only a cheap static check: it doesn't look inside `or`, `not` or `when`,
+    /// and most contradictions between other kinds of facts will go unnoticed.
+    ///
+    /// ```
+    /// # #[cfg(feature = "utils")] {
+    /// use contrafact::{*, facts::*};
+    ///
+    /// let fact = facts![
+    ///     lens1("0", |t: &mut (u8, u8)| &mut t.0, in_range("small", 0..10)),
+    ///     lens1("1", |t: &mut (u8, u8)| &mut t.1, eq(20)),
+    ///     lens1("0", |t: &mut (u8, u8)| &mut t.0, eq(20)),
+    /// ];
+    /// let contradictions = fact.describe().contradictions();
+    /// assert_eq!(contradictions.len(), 1);
+    /// assert_eq!(contradictions[0].path, "0");
+    /// # }
+    /// ```
+    pub fn contradictions(&self) -> Vec<Contradiction> {
+        let mut leaves = vec![];
+        self.constrained_leaves(&mut vec![], &mut leaves);
+
+        let mut contradictions = vec![];
+        for (i, (path, first, a)) in leaves.iter().enumerate() {
+            for (other_path, second, b) in leaves[i + 1..].iter() {
+                if path == other_path && a.contradicts(b) {
+                    contradictions.push(Contradiction {
+                        path: path.clone(),
+                        first: format!("{} ({})", first, a),
+                        second: format!("{} ({})", second, b),
+                    });
+                }
+            }
+        }
+        contradictions
+    }
+
+    /// Collect the path, label, and constraint of every constrained fact which
+    /// must hold along with all the others
+    fn constrained_leaves(
+        &self,
+        path: &mut Vec<String>,
+        leaves: &mut Vec<(String, String, Constraint)>,
+    ) {
+        if let Some(constraint) = &self.constraint {
+            leaves.push((path.join("/"), self.label.clone(), constraint.clone()));
+        }
+        let segment = match self.kind.as_str() {
+            "lens" | "prism" | "json_field" => Some(self.label.clone()),
+            "vec" => Some("[*]".to_string()),
+            "and" | "guard" | "json_object" | "fact" => None,
+            // Other facts like `or` and `when` don't require all of their parts to hold
+            _ => return,
+        };
+        if let Some(segment) = &segment {
+            path.push(segment.clone());
+        }
+        for child in self.children.iter() {
+            child.constrained_leaves(path, leaves);
+        }
+        if segment.is_some() {
+            path.pop();
+        }
+    }
+}
+
+/// A constraint simple enough to be compared with others without any data.
+/// Values are represented by their `Debug` output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Constraint {
+    /// Equal to this value
+    Eq(String),
+    /// Within these bounds
+    InRange(Bound<String>, Bound<String>),
+    /// Equal to one of these values
+    InSlice(Vec<String>),
+}
+
+impl Constraint {
+    /// Whether both constraints obviously can't be met at once.
+    /// Ranges can only be compared for integers.
+    pub fn contradicts(&self, other: &Constraint) -> bool {
+        use Constraint::*;
+        match (self, other) {
+            (Eq(a), Eq(b)) => a != b,
+            (Eq(a), InSlice(s)) | (InSlice(s), Eq(a)) => !s.contains(a),
+            (InSlice(s), InSlice(r)) => !s.iter().any(|x| r.contains(x)),
+            (Eq(a), InRange(lo, hi)) | (InRange(lo, hi), Eq(a)) => {
+                range_contains(lo, hi, a) == Some(false)
+            }
+            (InSlice(s), InRange(lo, hi)) | (InRange(lo, hi), InSlice(s)) => {
+                s.iter().all(|x| range_contains(lo, hi, x) == Some(false))
+            }
+            (InRange(lo, hi), InRange(lo2, hi2)) => {
+                let (Some(lo), Some(hi), Some(lo2), Some(hi2)) = (
+                    int_bound(lo, true),
+                    int_bound(hi, false),
+                    int_bound(lo2, true),
+                    int_bound(hi2, false),
+                ) else {
+                    return false;
+                };
+                lo.max(lo2) > hi.min(hi2)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Constraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Constraint::Eq(a) => write!(f, "== {}", a),
+            Constraint::InRange(lo, hi) => {
+                match lo {
+                    Bound::Included(lo) => write!(f, "in {}..", lo)?,
+                    Bound::Excluded(lo) => write!(f, "in >{}..", lo)?,
+                    Bound::Unbounded => write!(f, "in ..")?,
+                }
+                match hi {
+                    Bound::Included(hi) => write!(f, "={}", hi),
+                    Bound::Excluded(hi) => write!(f, "{}", hi),
+                    Bound::Unbounded => Ok(()),
+                }
+            }
+            Constraint::InSlice(s) => write!(f, "in [{}]", s.join(", ")),
+        }
+    }
+}
+
+/// An inclusive integer bound, or None if the bound isn't an integer
+fn int_bound(bound: &Bound<String>, lower: bool) -> Option<i128> {
+    match (bound, lower) {
+        (Bound::Included(x), _) => x.parse().ok(),
+        (Bound::Excluded(x), true) => x.parse::<i128>().ok().map(|x| x + 1),
+        (Bound::Excluded(x), false) => x.parse::<i128>().ok().map(|x| x - 1),
+        (Bound::Unbounded, true) => Some(i128::MIN),
+        (Bound::Unbounded, false) => Some(i128::MAX),
+    }
+}
+
+/// Whether an integer is within the bounds, or None if they can't be compared
+fn range_contains(lo: &Bound<String>, hi: &Bound<String>, x: &str) -> Option<bool> {
+    let x: i128 = x.parse().ok()?;
+    Some(int_bound(lo, true)? <= x && x <= int_bound(hi, false)?)
+}
+
+/// Two facts which apply to the same part of the data and can't both be met.
+/// See [`Description::contradictions`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Contradiction {
+    /// The path to the part of the data which both facts apply to
+    pub path: String,
+    /// The label and constraint of one fact
+    pub first: String,
+    /// The label and constraint of the other fact
+    pub second: String,
+}
+
+impl std::fmt::Display for Description {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_text())
+    }
+}
+
+#[test]
+fn test_constraint_contradicts() {
+    use Constraint::*;
+    let s = |x: &str| x.to_string();
+    let range = |lo: i32, hi: i32| {
+        InRange(
+            Bound::Included(s(&lo.to_string())),
+            Bound::Excluded(s(&hi.to_string())),
+        )
+    };
+
+    assert!(Eq(s("1")).contradicts(&Eq(s("2"))));
+    assert!(!Eq(s("1")).contradicts(&Eq(s("1"))));
+    assert!(Eq(s("1")).contradicts(&InSlice(vec![s("2"), s("3")])));
+    assert!(!InSlice(vec![s("1"), s("2")]).contradicts(&InSlice(vec![s("2"), s("3")])));
+    assert!(Eq(s("10")).contradicts(&range(0, 10)));
+    assert!(!Eq(s("9")).contradicts(&range(0, 10)));
+    assert!(range(0, 10).contradicts(&range(10, 20)));
+    assert!(!range(0, 11).contradicts(&range(10, 20)));
+    assert!(InSlice(vec![s("10"), s("20")]).contradicts(&range(0, 10)));
+    // Non-integers are never considered contradictory with ranges
+    assert!(!Eq(s("\"a\"")).contradicts(&range(0, 10)));
+}
+
+#[test]
+fn test_dot() {
+    let d = Description::new("and", "and").with_children([
+        Description::new("lens", "S::x").with_children([Description::new("fact", "say \"hi\"")]),
+        Description::new("fact", "eq"),
+    ]);
+    assert_eq!(
+        d.to_dot(),
+        r#"digraph fact {
+  n0 [label="and"];
+  n1 [label="lens: S::x"];
+  n2 [label="fact: say \"hi\""];
+  n1 -> n2;
+  n0 -> n1;
+  n3 [label="fact: eq"];
+  n0 -> n3;
+}
+"#
+    );
+}
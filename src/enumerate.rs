@@ -0,0 +1,122 @@
+use std::marker::PhantomData;
+
+use crate::fact::satisfy_loop;
+use crate::*;
+
+/// An iterator over the distinct values which satisfy a Fact.
+/// See [`Fact::enumerate`].
+pub struct Enumerate<'a, F, T>
+where
+    F: Fact<'a, T>,
+    T: Target<'a>,
+{
+    fact: F,
+    enumeration: Enumeration,
+    /// How many more paths may be walked
+    paths_left: usize,
+    done: bool,
+    seen: Vec<T>,
+    _phantom: PhantomData<&'a T>,
+}
+
+impl<'a, F, T> Enumerate<'a, F, T>
+where
+    F: Fact<'a, T>,
+    T: Target<'a>,
+{
+    pub(crate) fn new(fact: F, limit: usize) -> Self {
+        Self {
+            fact,
+            enumeration: Enumeration::new(),
+            paths_left: limit,
+            done: false,
+            seen: vec![],
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Whether every path was walked, so that every satisfying value reachable
+    /// by the fact has been produced
+    pub fn is_exhaustive(&self) -> bool {
+        self.done
+    }
+}
+
+impl<'a, F, T> Iterator for Enumerate<'a, F, T>
+where
+    F: Fact<'a, T>,
+    T: Target<'a> + for<'u> arbitrary::Arbitrary<'u>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while !self.done && self.paths_left > 0 {
+            self.paths_left -= 1;
+            let mut fact = self.fact.clone();
+            let mut g = Generator::new(&mut self.enumeration);
+            let value = g
+                .arbitrary(|| "enumerate")
+                .ok()
+                .and_then(|t| satisfy_loop(&mut fact, &mut g, t, None).ok()?.ok());
+            drop(g);
+            self.done = !self.enumeration.next_path();
+            if let Some(value) = value {
+                if !self.seen.contains(&value) {
+                    self.seen.push(value.clone());
+                    return Some(value);
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "utils")]
+mod tests {
+    use crate::facts::*;
+    use crate::*;
+
+    #[test]
+    fn test_enumerate_small_domains() {
+        assert_eq!(eq(3u8).enumerate(1000).collect::<Vec<_>>(), vec![3]);
+
+        let mut values: Vec<u8> = in_slice_(&[7, 1, 4]).enumerate(1000).collect();
+        values.sort();
+        assert_eq!(values, vec![1, 4, 7]);
+
+        let mut values: Vec<u8> = in_range("small", 0..=5).enumerate(1000).collect();
+        values.sort();
+        assert_eq!(values, vec![0, 1, 2, 3, 4, 5]);
+
+        let mut e = eq(true).enumerate(1000);
+        assert_eq!(e.next(), Some(true));
+        assert_eq!(e.next(), None);
+        assert!(e.is_exhaustive());
+    }
+
+    #[test]
+    fn test_enumerate_prism() {
+        #[derive(Debug, Clone, PartialEq, arbitrary::Arbitrary)]
+        enum E {
+            X(bool),
+            Y(u8),
+            Z,
+        }
+
+        fn y(e: &mut E) -> Option<&mut u8> {
+            match e {
+                E::Y(y) => Some(y),
+                _ => None,
+            }
+        }
+
+        let values: Vec<E> = prism("E::Y", y, in_slice_(&[1, 2]))
+            .enumerate(100_000)
+            .collect();
+        for e in [E::X(false), E::X(true), E::Y(1), E::Y(2), E::Z] {
+            assert!(values.contains(&e), "{:?} is missing", e);
+        }
+        assert_eq!(values.len(), 5);
+    }
+}
@@ -1,12 +1,17 @@
+use crate::Conflict;
+
 /// A failure is the reason why some data does not conform to a given Fact
 pub type Failure = String;
 
-// ///
-// #[derive(Clone, Debug, PartialEq, Eq, derive_more::From)]
-// pub enum CheckError {
-//     Check(Failure),
-//     Internal(ContrafactError),
-// }
+/// Why a [`Check`](crate::Check) didn't pass, as given by
+/// [`Check::into_result`](crate::Check::into_result)
+#[derive(Clone, Debug, PartialEq, Eq, derive_more::From)]
+pub enum CheckError {
+    /// The data doesn't meet the constraints, for these reasons
+    Failures(Vec<Failure>),
+    /// There was a problem running the check
+    Internal(ContrafactError),
+}
 
 /// Errors caused by bugs in Facts, Generators, or contrafact itself
 #[derive(Clone, Debug, PartialEq, Eq, derive_more::From)]
@@ -14,7 +19,10 @@ pub enum ContrafactError {
     // TODO: uncomment if this PR is merged:
     // https://github.com/rust-fuzz/arbitrary/pull/153
     // UnexpectedError(arbitrary::Error),
-    /// The only type of error we know about right now
+    /// Facts were found fighting over the same data during `satisfy`
+    Conflict(Conflict),
+
+    /// Any other error
     Other(String),
 }
 
@@ -26,10 +34,19 @@ pub type ContrafactResult<T> = Result<T, ContrafactError>;
 pub enum MutationError {
     /// When running check, this is a failure which was generated instead of mutating the data
     Check(Failure),
+
+    /// When running check, a precondition failed, so no further checks should occur.
+    /// See [`Generator::precondition`](crate::Generator::precondition).
+    Abort(Failure),
     /// arbitrary failed to produce new data, which means we can't go on
+    #[cfg(feature = "arbitrary")]
     #[from]
     Arbitrary(arbitrary::Error),
 
+    /// The Generator ran out of entropy before the mutation could complete.
+    /// See [`Generator::with_fallback`](crate::Generator::with_fallback) to avoid this.
+    Exhausted,
+
     /// Contrafact experienced a problem
     #[from]
     Internal(ContrafactError),
@@ -42,7 +59,10 @@ impl PartialEq for MutationError {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::Check(s), Self::Check(o)) => s == o,
+            (Self::Abort(s), Self::Abort(o)) => s == o,
+            #[cfg(feature = "arbitrary")]
             (Self::Arbitrary(s), Self::Arbitrary(o)) => s.to_string() == o.to_string(),
+            (Self::Exhausted, Self::Exhausted) => true,
             (Self::Internal(s), Self::Internal(o)) => s == o,
             (Self::User(s), Self::User(o)) => s == o,
             _ => false,
@@ -55,7 +75,7 @@ pub type Mutation<T> = Result<T, MutationError>;
 
 /// Adds a helpful method to MutationResults
 pub trait MutationExt<T> {
-    /// Map over only the Failures, leaving other error kinds untouched
+    /// Map over only the Failures (including an Abort), leaving other error kinds untouched
     fn map_check_err(self, f: impl Fn(Failure) -> Failure) -> Mutation<T>;
 }
 
@@ -63,6 +83,7 @@ impl<T> MutationExt<T> for Mutation<T> {
     fn map_check_err(self, f: impl Fn(Failure) -> Failure) -> Mutation<T> {
         match self {
             Err(MutationError::Check(e)) => Err(MutationError::Check(f(e))),
+            Err(MutationError::Abort(e)) => Err(MutationError::Abort(f(e))),
             other => other,
         }
     }
@@ -1,17 +1,15 @@
-use arbitrary::*;
 use either::Either;
 
+#[cfg(feature = "arbitrary")]
+use crate::coverage::steer;
 use crate::*;
 
 /// The trait bounds for the target of a Fact
-pub trait Target<'a>:
-    'a + std::fmt::Debug + Clone + Send + Sync + PartialEq + Arbitrary<'a>
-{
-}
-impl<'a, T> Target<'a> for T where
-    T: 'a + std::fmt::Debug + Clone + Send + Sync + PartialEq + Arbitrary<'a>
-{
-}
+///
+/// Targets don't need to implement `Arbitrary`. Values of types which do can be
+/// built with [`Fact::build`], and all others with [`Fact::build_with`].
+pub trait Target<'a>: 'a + std::fmt::Debug + Clone + Send + Sync + PartialEq {}
+impl<'a, T> Target<'a> for T where T: 'a + std::fmt::Debug + Clone + Send + Sync + PartialEq {}
 
 /// The trait bounds for the State of a Fact
 pub trait State: std::fmt::Debug + Clone + Send + Sync {}
@@ -20,14 +18,26 @@ impl<T> State for T where T: std::fmt::Debug + Clone + Send + Sync {}
 /// A declarative representation of a constraint on some data, which can be
 /// used to both make an assertion (check) or to mold some arbitrary existing
 /// data into a shape which passes that same assertion (mutate)
-pub trait Fact<'a, T>: Send + Sync + Clone + std::fmt::Debug
+pub trait Fact<'a, T>: 'a + Send + Sync + Clone + std::fmt::Debug
 where
     T: Target<'a>,
 {
-    /// Change the label
+    /// Change the label.
+    ///
+    /// A label given this way is kept as is, and goes before each failure from
+    /// within this fact, ahead of the parts of the data the failure is about:
+    /// e.g. `[1]: one: S::x: expected 2 == 1` for `vec(lens1("S::x", ..).labeled("one"))`.
+    /// Only the nearest label is used, so a failure from within several labeled
+    /// facts has just the innermost one's.
+    /// Labeled facts are checked by mutation rather than through [`Fact::check_mut`],
+    /// since the label is added as each failure is made.
     fn labeled(self, label: impl ToString) -> Self;
 
-    /// Get the label of this fact
+    /// Get the label of this fact.
+    ///
+    /// This is the label given with [`Fact::labeled`] or when the fact was made,
+    /// e.g. the label of a `lens1` or `brute`. Otherwise, combinators are
+    /// labeled after the facts inside them, e.g. `vec/eq(1)` or `and/{eq(1), eq(2)}`.
     fn label(&self) -> String;
 
     /// Assert that the constraint is satisfied for given data.
@@ -36,16 +46,89 @@ where
     /// by using a special Generator which fails upon mutation. If this is for
     /// some reason unreasonable, a check function can be written by hand, but
     /// care must be taken to make sure it perfectly lines up with the mutation function.
+    ///
+    /// Combinators of several facts keep checking after one of them fails
+    /// (see [`Generator::accumulate`]), so every failure is reported.
+    ///
+    /// Stateless facts (see [`Fact::is_stateless`]) are checked through
+    /// [`Fact::check_ref`], which clones the value once, and then reads through
+    /// references into it wherever the facts support [`Fact::check_mut`].
     #[tracing::instrument(fields(fact_impl = "Fact"), skip(self))]
     fn check(mut self, t: &T) -> Check {
+        if self.is_stateless() {
+            return self.check_ref(t);
+        }
+        let mut g = Generator::checker();
+        let result = self.mutate(&mut g, t.clone());
+        g.into_check(result)
+    }
+
+    /// Check a value without consuming this fact or changing its state.
+    ///
+    /// The value is cloned once, and then checked with [`Fact::check_mut`].
+    fn check_ref(&self, t: &T) -> Check {
+        self.check_mut(&mut t.clone())
+    }
+
+    /// Check a value by reading through references into it, rather than by
+    /// running a mutation over a clone of it, and without changing the state of this fact.
+    ///
+    /// The value is borrowed mutably only so that accessors like those given to
+    /// [`lens1`](crate::facts::lens1) and [`prism`](crate::facts::prism) can reach
+    /// into it. It must not be changed.
+    ///
+    /// The default clones this fact and the value, and runs a check with
+    /// [`Fact::mutate`]. Combinators like `lens1`, `prism`, `vec` and `and` override
+    /// this to pass references to their parts down to the facts inside them, when
+    /// those facts are stateless. While tracing is enabled at the TRACE level, they
+    /// check by mutation instead, so that every fact emits its span with its path.
+    fn check_mut(&self, t: &mut T) -> Check {
         let mut g = Generator::checker();
-        Check::from_mutation(self.mutate(&mut g, t.clone()))
+        let result = self.clone().mutate(&mut g, t.clone());
+        g.into_check(result)
+    }
+
+    /// Check each of a slice of values, in parallel if this fact is stateless
+    /// (see [`Fact::is_stateless`]). A stateful fact checks the values in order,
+    /// carrying its state from each to the next, as [`check_seq`] does.
+    ///
+    /// Each failure is prefixed with the index of the item it's about, and the
+    /// failures are in the order of the items either way.
+    /// In parallel, each value is checked with [`Fact::check_ref`], so this fact
+    /// isn't cloned for each of them.
+    ///
+    /// ```
+    /// use contrafact::{*, facts::*};
+    ///
+    /// let items: Vec<u32> = (0..1000).collect();
+    /// let check = in_range("small", 0..998).par_check(&items);
+    /// assert_eq!(
+    ///     check.failures().unwrap(),
+    ///     &[
+    ///         "[998]: small: expected 998 to be contained in 0..998".to_string(),
+    ///         "[999]: small: expected 999 to be contained in 0..998".to_string(),
+    ///     ]
+    /// );
+    /// assert!(consecutive_int_(0).par_check(&items).is_ok());
+    /// ```
+    #[cfg(feature = "rayon")]
+    fn par_check(self, items: &[T]) -> Check {
+        use rayon::prelude::*;
+
+        if !self.is_stateless() {
+            return check_seq(items, self);
+        }
+        let checks: Vec<Check> = items.par_iter().map(|t| self.check_ref(t)).collect();
+        crate::iter::merge_item_checks(checks)
     }
 
     /// Apply a mutation which moves the t closer to satisfying the overall
     /// constraint.
-    // #[tracing::instrument(skip(self, g))]
-    fn mutate(&mut self, g: &mut Generator<'a>, t: T) -> Mutation<T>;
+    ///
+    /// Facts built with [`lambda`] emit a tracing span for each call, with the
+    /// label, the [`Generator::path`], and the mode (check or mutate), and an
+    /// event whenever the value is changed.
+    fn mutate(&mut self, g: &mut Generator<'_>, t: T) -> Mutation<T>;
 
     /// Make this many attempts to satisfy a constraint before giving up and panicking.
     ///
@@ -58,43 +141,252 @@ where
         SATISFY_ATTEMPTS
     }
 
+    /// Describe this fact and the facts it is made of, as a tree.
+    ///
+    /// Facts which aren't made of other facts are described by their label.
+    fn describe(&self) -> Description {
+        Description::new("fact", self.label())
+    }
+
+    /// Whether this fact never changes its state, so that it gives the same
+    /// result for a value no matter what it has seen before. Stateless facts
+    /// can check many values at once, in parallel.
+    ///
+    /// Facts are taken to be stateful unless they say otherwise. Combinators
+    /// are stateless when all of the facts inside them are.
+    fn is_stateless(&self) -> bool {
+        false
+    }
+
+    /// Take a snapshot of this fact's mutable state, which can be used to roll
+    /// the fact back with [`Fact::restore_state`].
+    ///
+    /// The default clones the entire fact. Combinators override this so that only
+    /// the state of the facts inside them is copied, and a fact whose state can't
+    /// be rolled back by cloning (e.g. state behind an `Arc`) can override it to
+    /// do the right thing.
+    fn save_state(&self) -> Snapshot<'a, Self> {
+        let this = self.clone();
+        Snapshot::new(move |fact| *fact = this.clone())
+    }
+
+    /// Roll back to a snapshot taken with [`Fact::save_state`].
+    fn restore_state(&mut self, snapshot: &Snapshot<'a, Self>) {
+        snapshot.restore(self)
+    }
+
     /// Mutate a value such that it satisfies the constraint.
     /// If the constraint cannot be satisfied, panic.
+    ///
+    /// If the facts are found to be fighting each other, so that the same few values
+    /// keep coming up, give up early with a [`ContrafactError::Conflict`] describing
+    /// which facts are in conflict over which parts of the value.
+    ///
+    /// If a mutation doesn't lead to a value which passes the check, the state
+    /// of this fact is rolled back before the next attempt.
     #[tracing::instrument(fields(fact_impl = "Fact"), skip(self, g))]
-    fn satisfy(&mut self, g: &mut Generator<'a>, t: T) -> ContrafactResult<T> {
+    fn satisfy(&mut self, g: &mut Generator<'_>, t: T) -> ContrafactResult<T> {
         tracing::trace!("satisfy");
-        let mut last_failure: Vec<String> = vec![];
-        let mut next = t.clone();
-        for _i in 0..self.satisfy_attempts() {
-            let mut m = self.clone();
-            next = m.mutate(g, next).unwrap();
-            if let Err(errs) = self.clone().check(&next).result()? {
-                last_failure = errs;
-            } else {
-                *self = m;
-                return Ok(next);
-            }
+        match satisfy_loop(self, g, t, None)? {
+            Ok(t) => Ok(t),
+            Err(last_failure) => panic!(
+                "Could not satisfy a constraint even after {} attempts. Last check failure: {:?}",
+                SATISFY_ATTEMPTS, last_failure
+            ),
         }
-        panic!(
-            "Could not satisfy a constraint even after {} attempts. Last check failure: {:?}",
-            SATISFY_ATTEMPTS, last_failure
-        );
     }
 
+    /// Like [`Fact::satisfy`], but also report every change made to the value
+    /// during each attempt, and which fact made it. Rather than panicking if
+    /// the constraint can't be satisfied, an error is returned alongside the report,
+    /// which will usually show which facts are fighting over which parts of the value.
+    ///
+    /// ```
+    /// # #[cfg(feature = "utils")] {
+    /// use contrafact::{*, facts::*};
+    ///
+    /// let mut g = utils::random_generator();
+    /// let mut fact = lens1("0", |t: &mut (u8, u8)| &mut t.0, eq(1));
+    /// let (t, report) = fact.satisfy_with_report(&mut g, (0, 0));
+    /// assert_eq!(t.unwrap(), (1, 0));
+    ///
+    /// let change = &report.attempts[0].changes[0];
+    /// assert_eq!(change.path, "0");
+    /// assert_eq!(change.label, "eq(1)");
+    /// assert_eq!((change.old.as_str(), change.new.as_str()), ("0", "1"));
+    /// # }
+    /// ```
+    #[tracing::instrument(fields(fact_impl = "Fact"), skip(self, g))]
+    fn satisfy_with_report(
+        &mut self,
+        g: &mut Generator<'_>,
+        t: T,
+    ) -> (ContrafactResult<T>, SatisfyReport) {
+        let mut report = SatisfyReport::default();
+        let result = satisfy_loop(self, g, t, Some(&mut report)).and_then(|r| {
+            r.map_err(|last_failure| {
+                format!(
+                    "Could not satisfy a constraint even after {} attempts. Last check failure: {:?}",
+                    report.attempts.len(),
+                    last_failure
+                )
+                .into()
+            })
+        });
+        (result, report)
+    }
+
+    #[cfg(feature = "arbitrary")]
     #[tracing::instrument(fields(fact_impl = "Fact"), skip(self, g))]
     /// Build a new value such that it satisfies the constraint
-    fn build_fallible(mut self, g: &mut Generator<'a>) -> ContrafactResult<T> {
-        let t = T::arbitrary(g).unwrap();
-        self.satisfy(g, t)
+    fn build_fallible(mut self, g: &mut Generator<'_>) -> ContrafactResult<T>
+    where
+        T: for<'u> arbitrary::Arbitrary<'u>,
+    {
+        build_arbitrary(&mut self, g)
     }
 
     /// Build a new value such that it satisfies the constraint, panicking on error
+    #[cfg(feature = "arbitrary")]
     #[tracing::instrument(fields(fact_impl = "Fact"), skip(self, g))]
-    fn build(self, g: &mut Generator<'a>) -> T {
+    fn build(self, g: &mut Generator<'_>) -> T
+    where
+        T: for<'u> arbitrary::Arbitrary<'u>,
+    {
         self.build_fallible(g).unwrap()
     }
+
+    /// Build an endless series of values, each satisfying the constraint.
+    /// Unlike calling [`Fact::build`] in a loop on clones of the fact, the state
+    /// of the fact carries over from each value to the next.
+    ///
+    /// ```
+    /// use contrafact::{*, facts::*};
+    ///
+    /// let mut g = utils::random_generator();
+    /// let ids: Vec<u32> = consecutive_int_(1).build_iter(&mut g).take(3).collect();
+    /// assert_eq!(ids, vec![1, 2, 3]);
+    /// ```
+    #[cfg(feature = "arbitrary")]
+    fn build_iter<'b, 'g>(self, g: &'b mut Generator<'g>) -> BuildIter<'a, 'b, 'g, Self, T>
+    where
+        T: for<'u> arbitrary::Arbitrary<'u>,
+    {
+        BuildIter::new(self, g)
+    }
+
+    /// Build `n` values, carrying the state of the fact over from each to the next.
+    /// See [`Fact::build_iter`].
+    #[cfg(feature = "arbitrary")]
+    fn build_n(self, g: &mut Generator<'_>, n: usize) -> Vec<T>
+    where
+        T: for<'u> arbitrary::Arbitrary<'u>,
+    {
+        self.build_iter(g).take(n).collect()
+    }
+
+    /// Check each of a series of values, carrying the state of the fact over
+    /// from each to the next, and yielding a [`Check`] for each value.
+    /// The values are checked lazily, so this works with endless streams too.
+    ///
+    /// ```
+    /// use contrafact::{*, facts::*};
+    ///
+    /// let checks: Vec<bool> = consecutive_int_(1)
+    ///     .check_iter([1, 2, 4])
+    ///     .map(|check| check.is_ok())
+    ///     .collect();
+    /// assert_eq!(checks, vec![true, true, false]);
+    /// ```
+    fn check_iter<I>(self, items: I) -> CheckIter<'a, Self, T, I::IntoIter>
+    where
+        I: IntoIterator,
+        I::Item: std::borrow::Borrow<T>,
+    {
+        CheckIter::new(self, items.into_iter())
+    }
+
+    /// Enumerate the distinct values which satisfy this fact, in a canonical order,
+    /// by walking every path through the choices made while building them
+    /// (see [`Enumeration`]).
+    ///
+    /// This is meant for facts with small domains, like `eq`, `in_slice`, small
+    /// `in_range`s, and enums constrained by `prism`, so that they can be tested
+    /// exhaustively and deterministically. At most `limit` paths are walked,
+    /// which bounds the time taken for facts with large domains.
+    ///
+    /// ```
+    /// use contrafact::{*, facts::*};
+    ///
+    /// let values: Vec<u8> = or(eq(1), in_slice_(&[2, 3])).enumerate(10_000).collect();
+    /// assert_eq!(values, vec![1, 2, 3]);
+    /// ```
+    #[cfg(feature = "arbitrary")]
+    fn enumerate(self, limit: usize) -> Enumerate<'a, Self, T>
+    where
+        T: for<'u> arbitrary::Arbitrary<'u>,
+    {
+        Enumerate::new(self, limit)
+    }
+
+    /// Build a new value such that it satisfies the constraint, using a custom
+    /// function to generate the initial value before it is mutated.
+    ///
+    /// This allows building values of types which don't implement `Arbitrary`.
+    #[tracing::instrument(fields(fact_impl = "Fact"), skip(self, g, generate))]
+    fn build_fallible_with(
+        mut self,
+        g: &mut Generator<'_>,
+        generate: impl FnOnce(&mut Generator<'_>) -> Mutation<T>,
+    ) -> ContrafactResult<T> {
+        build_from(&mut self, g, generate)
+    }
+
+    /// Build a new value such that it satisfies the constraint, using a custom
+    /// function to generate the initial value, panicking on error.
+    ///
+    /// ```
+    /// # #[cfg(feature = "utils")] {
+    /// use contrafact::*;
+    ///
+    /// // A type which doesn't implement Arbitrary
+    /// #[derive(Debug, Clone, PartialEq)]
+    /// struct Celsius(i16);
+    ///
+    /// let fact = lens1("Celsius", |c: &mut Celsius| &mut c.0, brute("not frozen", |t| *t > 0));
+    ///
+    /// let mut g = utils::random_generator();
+    /// let c = fact.build_with(&mut g, |g| Ok(Celsius(g.int_in_range(-100..=100, || "")?)));
+    /// assert!(c.0 > 0);
+    /// # }
+    /// ```
+    #[tracing::instrument(fields(fact_impl = "Fact"), skip(self, g, generate))]
+    fn build_with(
+        self,
+        g: &mut Generator<'_>,
+        generate: impl FnOnce(&mut Generator<'_>) -> Mutation<T>,
+    ) -> T {
+        self.build_fallible_with(g, generate).unwrap()
+    }
+}
+
+/// A snapshot of the mutable state of a Fact, which can be used to roll the
+/// Fact back to that state. See [`Fact::save_state`].
+pub struct Snapshot<'a, F>(Box<dyn 'a + Fn(&mut F)>);
+
+impl<'a, F> Snapshot<'a, F> {
+    /// Create a snapshot from a function which restores the state
+    pub fn new(restore: impl 'a + Fn(&mut F)) -> Self {
+        Self(Box::new(restore))
+    }
+
+    /// Roll back to this snapshot
+    pub fn restore(&self, fact: &mut F) {
+        (self.0)(fact)
+    }
 }
 
+#[allow(clippy::needless_maybe_sized)]
 impl<'a, T, F1, F2> Fact<'a, T> for Either<F1, F2>
 where
     T: Target<'a>,
@@ -102,7 +394,7 @@ where
     F2: Fact<'a, T> + ?Sized,
 {
     #[tracing::instrument(fields(fact_impl = "Either"), skip(self, g))]
-    fn mutate(&mut self, g: &mut Generator<'a>, t: T) -> Mutation<T> {
+    fn mutate(&mut self, g: &mut Generator<'_>, t: T) -> Mutation<T> {
         match self {
             Either::Left(f) => f.mutate(g, t),
             Either::Right(f) => f.mutate(g, t),
@@ -116,6 +408,27 @@ where
         }
     }
 
+    fn describe(&self) -> Description {
+        match self {
+            Either::Left(f) => f.describe(),
+            Either::Right(f) => f.describe(),
+        }
+    }
+
+    fn is_stateless(&self) -> bool {
+        match self {
+            Either::Left(f) => f.is_stateless(),
+            Either::Right(f) => f.is_stateless(),
+        }
+    }
+
+    fn check_mut(&self, t: &mut T) -> Check {
+        match self {
+            Either::Left(f) => f.check_mut(t),
+            Either::Right(f) => f.check_mut(t),
+        }
+    }
+
     fn labeled(self, label: impl ToString) -> Self {
         match self {
             Either::Left(f) => Either::Left(f.labeled(label)),
@@ -124,23 +437,182 @@ where
     }
 }
 
+/// Build a value from an arbitrary starting point. If the Generator is tracking
+/// [`Coverage`], the starting point is steered towards uncovered branches.
+#[cfg(feature = "arbitrary")]
+pub(crate) fn build_arbitrary<'a, T, F>(fact: &mut F, g: &mut Generator<'_>) -> ContrafactResult<T>
+where
+    T: Target<'a> + for<'u> arbitrary::Arbitrary<'u>,
+    F: Fact<'a, T>,
+{
+    if g.coverage().is_some() {
+        let probe = fact.clone();
+        return build_from(fact, g, |g| steer(&probe, g));
+    }
+    build_from(fact, g, |g| g.arbitrary(|| "build"))
+}
+
+/// Build a value from a starting point produced by `generate`, leaving the fact
+/// in its state after satisfying the constraint
+fn build_from<'a, T, F>(
+    fact: &mut F,
+    g: &mut Generator<'_>,
+    generate: impl FnOnce(&mut Generator<'_>) -> Mutation<T>,
+) -> ContrafactResult<T>
+where
+    T: Target<'a>,
+    F: Fact<'a, T>,
+{
+    let t = generate(g).map_err(|err| format!("{:?}", err))?;
+    match g.coverage().cloned() {
+        Some(coverage) => {
+            let before = fact.save_state();
+            let t = fact.satisfy(g, t)?;
+            let after = fact.save_state();
+            fact.restore_state(&before);
+            coverage.record(fact, &t);
+            fact.restore_state(&after);
+            Ok(t)
+        }
+        None => fact.satisfy(g, t),
+    }
+}
+
+/// Repeatedly mutate and check until the value passes, rolling back the state
+/// of the fact after each failed attempt. Returns the last failures if every
+/// attempt failed. If a report is given, the changes made during each attempt
+/// are recorded into it.
+pub(crate) fn satisfy_loop<'a, T, F>(
+    fact: &mut F,
+    g: &mut Generator<'_>,
+    t: T,
+    report: Option<&mut SatisfyReport>,
+) -> ContrafactResult<Result<T, Vec<Failure>>>
+where
+    T: Target<'a>,
+    F: Fact<'a, T>,
+{
+    let outer = g.swap_changes(report.is_some().then(Vec::new));
+    let result = attempt_satisfy(fact, g, t, report);
+    g.swap_changes(outer);
+    result
+}
+
+fn attempt_satisfy<'a, T, F>(
+    fact: &mut F,
+    g: &mut Generator<'_>,
+    t: T,
+    mut report: Option<&mut SatisfyReport>,
+) -> ContrafactResult<Result<T, Vec<Failure>>>
+where
+    T: Target<'a>,
+    F: Fact<'a, T>,
+{
+    let mut last_failure: Vec<Failure> = vec![];
+    let mut next = t;
+    let mut recent = std::collections::VecDeque::with_capacity(CONFLICT_REPEATS);
+    let mut repeats = 0;
+    for _i in 0..fact.satisfy_attempts() {
+        let before = fact.save_state();
+        next = fact
+            .mutate(g, next)
+            .map_err(|err| format!("mutation failed during satisfy: {:?}", err))?;
+        let after = fact.save_state();
+
+        // Check against the state from before the mutation
+        fact.restore_state(&before);
+        let mut checker = Generator::checker();
+        let result = fact.mutate(&mut checker, next.clone());
+        let failures = match checker.into_check(result).result()? {
+            Ok(()) => vec![],
+            Err(failures) => failures,
+        };
+        if let Some(report) = report.as_mut() {
+            let changes = g.swap_changes(Some(vec![])).unwrap_or_default();
+            report
+                .attempts
+                .push(Attempt::new(changes, failures.clone()));
+        }
+        if failures.is_empty() {
+            fact.restore_state(&after);
+            return Ok(Ok(next));
+        }
+        fact.restore_state(&before);
+
+        // Facts which keep producing the same few values are probably fighting
+        if recent.contains(&next) {
+            repeats += 1;
+        } else {
+            repeats = 0;
+        }
+        if repeats >= CONFLICT_REPEATS {
+            // Only give up early if there's evidence of a conflict. Some facts
+            // just take several passes over the same value.
+            let conflict = diagnose_conflict(fact, g, next.clone(), failures.clone());
+            if !conflict.overwrites.is_empty() || !conflict.contradictions.is_empty() {
+                return Err(conflict.into());
+            }
+            repeats = 0;
+        }
+        if recent.len() == CONFLICT_REPEATS {
+            recent.pop_front();
+        }
+        recent.push_back(next.clone());
+        last_failure = failures;
+    }
+    Ok(Err(last_failure))
+}
+
+/// Repeat the last attempt, recording changes to see which facts are overwriting
+/// each other, and look for facts which statically contradict each other.
+fn diagnose_conflict<'a, T, F>(
+    fact: &mut F,
+    g: &mut Generator<'_>,
+    t: T,
+    failures: Vec<Failure>,
+) -> Conflict
+where
+    T: Target<'a>,
+    F: Fact<'a, T>,
+{
+    let before = fact.save_state();
+    let outer = g.swap_changes(Some(vec![]));
+    let _ = fact.mutate(g, t);
+    let changes = g.swap_changes(outer).unwrap_or_default();
+    fact.restore_state(&before);
+    Conflict {
+        overwrites: Attempt::new(changes, vec![]).overwrites,
+        contradictions: fact.describe().contradictions(),
+        failures,
+    }
+}
+
+/// Check one value against each of several facts of the same type,
+/// prefixing each failure with the index of the fact it's from.
+///
+/// Unlike combining the facts with [`facts!`], every fact is checked even if
+/// an earlier one aborts, and the failures say which fact they're from.
+///
+/// ```
+/// # #[cfg(feature = "utils")] {
+/// use contrafact::*;
+///
+/// let facts = vec![in_range("small", 0..10), in_range("big", 5..100)];
+/// assert_eq!(
+///     collect_checks(facts, &3).failures().unwrap(),
+///     &["fact 1: big: expected 3 to be contained in 5..100".to_string()]
+/// );
+/// # }
+/// ```
 #[tracing::instrument(skip(facts))]
-fn collect_checks<'a, T, F>(facts: Vec<F>, t: &T) -> Check
+pub fn collect_checks<'a, T, F>(facts: impl IntoIterator<Item = F>, t: &T) -> Check
 where
     T: Target<'a>,
     F: Fact<'a, T>,
 {
-    let checks = facts
+    facts
         .into_iter()
         .enumerate()
-        .map(|(i, f)| {
-            Ok(f.check(t)
-                .failures()?
-                .iter()
-                .map(|e| format!("fact {}: {}", i, e))
-                .collect())
-        })
-        .collect::<ContrafactResult<Vec<Vec<Failure>>>>()
-        .map(|fs| fs.into_iter().flatten().collect());
-    Check::from_result(checks)
+        .map(|(i, f)| f.check(t).with_context(format_args!("fact {}", i)))
+        .collect()
 }
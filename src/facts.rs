@@ -1,31 +1,54 @@
 mod and;
+#[cfg(feature = "arbitrary")]
 mod brute;
 mod consecutive_int;
 mod constant;
 mod eq;
+mod guard;
+#[cfg(feature = "arbitrary")]
 mod in_range;
 mod in_slice;
+#[cfg(feature = "json")]
+pub mod json;
 mod lens;
+#[cfg(feature = "arbitrary")]
 mod not;
 mod or;
 mod prism;
 mod same;
 mod seq;
+mod when;
 
 pub use consecutive_int::{consecutive_int, consecutive_int_};
 pub use constant::{always, never};
-pub use eq::{eq, ne};
-pub use in_range::in_range;
-pub use in_slice::{in_slice, in_slice_};
-pub use not::not;
+pub use eq::eq;
+pub use guard::guard;
+pub use in_slice::{in_slice, in_slice_, weighted_slice};
 pub use or::or;
-pub use same::{different, same};
+pub use same::same;
 
 pub use and::and;
-pub use brute::brute;
 pub use lens::{lens1, lens2};
 pub use prism::prism;
-pub use seq::{vec, vec_len, vec_of_length};
+#[cfg(feature = "rayon")]
+pub use seq::par_vec;
+pub use seq::vec;
+pub use when::{implies, when};
+
+// Facts which generate arbitrary data for their mutations
+#[cfg(feature = "arbitrary")]
+pub use brute::brute;
+#[cfg(feature = "arbitrary")]
+pub use eq::ne;
+
+#[cfg(feature = "arbitrary")]
+pub use in_range::{in_range, in_range_edges};
+#[cfg(feature = "arbitrary")]
+pub use not::not;
+#[cfg(feature = "arbitrary")]
+pub use same::different;
+#[cfg(feature = "arbitrary")]
+pub use seq::{vec_len, vec_of_length};
 
 // Optical facts are experimental and currently not supported
 // #[cfg(feature = "optics")]
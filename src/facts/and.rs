@@ -1,13 +1,47 @@
 use crate::*;
 
 /// A Fact which applies two other facts.
-pub fn and<'a, T>(a: impl Fact<'a, T>, b: impl Fact<'a, T>) -> impl Fact<'a, T>
+pub fn and<'a, T, A, B>(a: A, b: B) -> impl Fact<'a, T>
 where
     T: Target<'a>,
+    A: Fact<'a, T>,
+    B: Fact<'a, T>,
 {
     lambda("and", (a, b), |g, (a, b), t| {
-        let t = a.mutate(g, t)?;
+        let t = g.accumulate(t, |g, t| a.mutate(g, t))?;
         let t = b.mutate(g, t)?;
         Ok(t)
     })
+    .describe_with(|label, (a, b)| {
+        // Flatten nested `and`s, as built by `facts!`, into a single list
+        let children = [a.describe(), b.describe()].into_iter().flat_map(|d| {
+            if d.kind == "and" && d.label == "and" {
+                d.children
+            } else {
+                vec![d]
+            }
+        });
+        Description::new("and", label).with_children(children)
+    })
+    .label_with(|label, (a, b)| label_children(label, &[a.label(), b.label()]))
+    .snapshot_pair()
+    .check_mut_with(|(a, b), t| a.check_mut(t).and_then(|| b.check_mut(t)))
+    .stateless_with(|(a, b)| a.is_stateless() && b.is_stateless())
+}
+
+#[test]
+fn test_and_reports_every_failure() {
+    observability::test_run().ok();
+
+    let fact = facts![
+        lens1("0", |t: &mut (u8, u8, u8)| &mut t.0, eq(1)),
+        lens1("1", |t: &mut (u8, u8, u8)| &mut t.1, eq(2)),
+        lens1("2", |t: &mut (u8, u8, u8)| &mut t.2, eq(3)),
+    ];
+    fact.clone().check(&(1, 2, 3)).unwrap();
+
+    let failures = fact.check(&(0, 2, 0)).result().unwrap().unwrap_err();
+    assert_eq!(failures.len(), 2);
+    assert!(failures[0].starts_with("0: "));
+    assert!(failures[1].starts_with("2: "));
 }
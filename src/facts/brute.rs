@@ -22,10 +22,9 @@ use crate::*;
 /// There is a fixed iteration limit, beyond which this will panic.
 ///
 /// ```
-/// use arbitrary::Unstructured;
 /// use contrafact::*;
 ///
-/// fn div_by(n: usize) -> impl Fact<'static, usize> {
+/// fn div_by<'a>(n: usize) -> impl Fact<'a, usize> {
 ///     facts![brute(format!("Is divisible by {}", n), move |x| x % n == 0)]
 /// }
 ///
@@ -34,22 +33,32 @@ use crate::*;
 /// ```
 pub fn brute<'a, T, F>(label: impl ToString, f: F) -> Lambda<'a, (), T>
 where
-    T: Target<'a>,
+    T: Target<'a> + for<'u> arbitrary::Arbitrary<'u>,
     F: 'a + Send + Sync + Fn(&T) -> bool,
 {
     let label = label.to_string();
-    // TODO figure out this label stuff
-    let label2 = label.clone();
-    brute_labeled(move |v| Ok(f(v).then_some(()).ok_or_else(|| label.clone()))).labeled(label2)
+    let reason = label.clone();
+    brute_with(label, move |v| {
+        Ok(f(v).then_some(()).ok_or_else(|| reason.clone()))
+    })
 }
 
 /// A version of [`brute`] which allows the closure to return the reason for failure
 pub fn brute_labeled<'a, T, F>(f: F) -> Lambda<'a, (), T>
 where
-    T: Target<'a>,
+    T: Target<'a> + for<'u> arbitrary::Arbitrary<'u>,
+    F: 'a + Send + Sync + Fn(&T) -> ContrafactResult<BruteResult>,
+{
+    brute_with("brute_labeled", f)
+}
+
+/// The brute force search behind both [`brute`] and [`brute_labeled`]
+fn brute_with<'a, T, F>(label: impl ToString, f: F) -> Lambda<'a, (), T>
+where
+    T: Target<'a> + for<'u> arbitrary::Arbitrary<'u>,
     F: 'a + Send + Sync + Fn(&T) -> ContrafactResult<BruteResult>,
 {
-    lambda_unit("brute_labeled", move |g, mut t| {
+    lambda_unit(label, move |g, mut t| {
         let mut last_reason = "".to_string();
         for _ in 0..=BRUTE_ITERATION_LIMIT {
             if let Err(reason) = f(&t)? {
@@ -9,7 +9,7 @@ where
     lambda("consecutive_int", initial, move |g, counter, mut t| {
         if t != *counter {
             g.fail(&context)?;
-            t = counter.clone();
+            t = *counter;
         }
         *counter = counter.checked_add(&S::from(1).unwrap()).unwrap();
         Ok(t)
@@ -9,7 +9,7 @@ pub fn always<'a, T: Target<'a>>() -> Lambda<'a, (), T> {
 pub fn never<'a, T: Target<'a>>(context: impl ToString) -> Lambda<'a, (), T> {
     let context = context.to_string();
     lambda_unit("never", move |g, t: T| {
-        g.fail(context.clone())?;
+        g.fail(&context)?;
         Ok(t)
     })
 }
@@ -6,25 +6,31 @@ where
     T: Target<'a> + PartialEq + Clone,
 {
     let label = format!("eq({:?})", constant);
+    let constraint = Constraint::Eq(format!("{:?}", constant));
     lambda_unit(label, move |g, mut t| {
         if t != constant {
-            g.fail(format!("expected {:?} == {:?}", t, constant))?;
+            g.fail_with(|| format!("expected {:?} == {:?}", t, constant))?;
             t = constant.clone();
         }
         Ok(t)
     })
+    .describe_with(move |label, ()| {
+        Description::new("fact", label).with_constraint(constraint.clone())
+    })
 }
 
 /// Specifies an inequality constraint
+#[cfg(feature = "arbitrary")]
 pub fn ne<'a, S, T>(constant: T) -> Lambda<'a, (), T>
 where
     S: ToString,
-    T: Target<'a> + PartialEq,
+    T: Target<'a> + PartialEq + for<'u> arbitrary::Arbitrary<'u>,
 {
     not(eq(constant)).labeled("ne")
 }
 
 #[test]
+#[cfg(feature = "utils")]
 fn test_eq() {
     observability::test_run().ok();
     let mut g = utils::random_generator();
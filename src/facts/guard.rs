@@ -0,0 +1,100 @@
+use super::*;
+
+/// A Fact which only applies `fact` once `precondition` holds.
+///
+/// When checking, if the precondition fails then `fact` is skipped, so that facts
+/// which only make sense when the precondition holds don't produce a pile of
+/// follow-on failures. The precondition's failure is reported like any other,
+/// so facts alongside the guard are still checked.
+/// When mutating, the precondition is satisfied first.
+///
+/// ```
+/// # #[cfg(feature = "utils")] {
+/// use contrafact::{*, facts::*};
+///
+/// let fact = guard(
+///     brute("non-empty", |v: &Vec<u8>| !v.is_empty()),
+///     lens1("first", |v: &mut Vec<u8>| &mut v[0], eq(1)),
+/// );
+/// assert_eq!(fact.clone().check(&vec![]), Check::Failures(vec!["non-empty".to_string()]));
+/// assert!(fact.clone().check(&vec![2]).is_err());
+/// fact.check(&vec![1]).unwrap();
+/// # }
+/// ```
+pub fn guard<'a, T>(precondition: impl Fact<'a, T>, fact: impl Fact<'a, T>) -> impl Fact<'a, T>
+where
+    T: Target<'a>,
+{
+    lambda(
+        "guard",
+        (precondition, fact),
+        |g, (precondition, fact), t| {
+            // Only the guarded fact is skipped, not whatever comes after the guard
+            let t = match g.precondition(t, |g, t| precondition.mutate(g, t)) {
+                Err(MutationError::Abort(failure)) => return Err(MutationError::Check(failure)),
+                t => t?,
+            };
+            fact.mutate(g, t)
+        },
+    )
+    .describe_with(|label, (precondition, fact)| {
+        Description::new("guard", label).with_children([precondition.describe(), fact.describe()])
+    })
+    .label_with(|label, (precondition, fact)| {
+        label_children(label, &[precondition.label(), fact.label()])
+    })
+    .check_mut_with(|(precondition, fact), t| match precondition.check_mut(t) {
+        Check::Failures(failures) if failures.is_empty() => fact.check_mut(t),
+        Check::Failures(failures) | Check::Abort(failures) => Check::Failures(failures),
+        error => error,
+    })
+    .snapshot_pair()
+    .stateless_with(|(precondition, fact)| precondition.is_stateless() && fact.is_stateless())
+}
+
+#[test]
+#[cfg(feature = "utils")]
+#[cfg(feature = "arbitrary")]
+fn test_guard() {
+    observability::test_run().ok();
+    let mut g = utils::random_generator();
+
+    let fact = || {
+        facts![
+            guard(
+                brute("len >= 2", |v: &Vec<u8>| v.len() >= 2),
+                facts![
+                    lens1("[0]", |v: &mut Vec<u8>| &mut v[0], eq(1)),
+                    lens1("[1]", |v: &mut Vec<u8>| &mut v[1], eq(2)),
+                ]
+            ),
+            brute("len <= 4", |v: &Vec<u8>| v.len() <= 4),
+        ]
+    };
+
+    assert_eq!(
+        fact().check(&vec![1, 2, 3, 4, 5]),
+        Check::Failures(vec!["len <= 4".to_string()])
+    );
+    // The guarded facts are skipped, but the facts alongside the guard are not
+    let starts_with_1 = || brute("starts with 1", |v: &Vec<u8>| v.first() == Some(&1));
+    assert_eq!(
+        facts![fact(), starts_with_1()].check(&vec![9]),
+        Check::Failures(vec!["len >= 2".to_string(), "starts with 1".to_string()])
+    );
+    // ...including when a stateful fact alongside means the check runs by mutation
+    let stateful = lambda("stateful", (), |_, (), v| Ok(v));
+    assert_eq!(
+        facts![fact(), starts_with_1(), stateful].check(&vec![9]),
+        Check::Failures(vec!["len >= 2".to_string(), "starts with 1".to_string()])
+    );
+    assert_eq!(
+        vec(fact()).check(&vec![vec![9], vec![1, 2], vec![9]]).len(),
+        2
+    );
+    assert_eq!(fact().check(&vec![9, 9]).failures().unwrap().len(), 2);
+
+    let v = fact().build(&mut g);
+    fact().check(&v).unwrap();
+    assert_eq!(&v[..2], &[1, 2]);
+}
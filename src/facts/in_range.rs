@@ -13,42 +13,144 @@ where
         + std::ops::Add<Output = T>
         + std::ops::Sub<Output = T>
         + num::Bounded
-        + num::One,
+        + num::One
+        + for<'u> arbitrary::Arbitrary<'u>,
 {
-    let context = context.to_string();
-    lambda_unit("in_range", move |g, mut t| {
+    range_fact("in_range", context.to_string(), range, arbitrary_in_range)
+}
+
+/// Specifies a range constraint, like [`in_range`], except that when a new value
+/// is chosen, then with a probability of `percent`%, it is one of the endpoints of
+/// the range, one of their neighbors, or zero. Off-by-one errors in code which uses
+/// the values will then show up quickly.
+///
+/// ```
+/// # #[cfg(feature = "utils")] {
+/// use contrafact::{*, facts::*};
+///
+/// let mut g = utils::random_generator();
+/// let fact = vec(in_range_edges("small", 10..20u32, 50));
+/// let xs = fact.clone().build(&mut g);
+/// fact.check(&xs).unwrap();
+/// assert!(xs.iter().all(|x| (10..20).contains(x)));
+/// # }
+/// ```
+pub fn in_range_edges<'a, R, T>(context: impl ToString, range: R, percent: u8) -> Lambda<'a, (), T>
+where
+    R: 'a + Send + Sync + RangeBounds<T> + std::fmt::Debug,
+    T: Target<'a>
+        + Int
+        + std::ops::Add<Output = T>
+        + std::ops::Sub<Output = T>
+        + num::Bounded
+        + num::One
+        + num::Zero,
+{
+    let start = match range.start_bound() {
+        Bound::Included(a) => *a,
+        Bound::Excluded(a) if *a < T::max_value() => *a + T::one(),
+        Bound::Excluded(_) => T::max_value(),
+        Bound::Unbounded => T::min_value(),
+    };
+    let end = match range.end_bound() {
+        Bound::Included(b) => *b,
+        Bound::Excluded(b) if *b > T::min_value() => *b - T::one(),
+        Bound::Excluded(_) => T::min_value(),
+        Bound::Unbounded => T::max_value(),
+    };
+    let mut edges = vec![start, end, T::zero()];
+    if start < end {
+        edges.push(start + T::one());
+        edges.push(end - T::one());
+    }
+    range_fact(
+        "in_range_edges",
+        context.to_string(),
+        range,
+        move |g, _, reason| g.int_with_edges(start..=end, edges.clone(), percent, reason),
+    )
+}
+
+/// A range constraint which uses `pick` to choose a new value within the range
+fn range_fact<'a, R, T>(
+    label: &str,
+    context: String,
+    range: R,
+    pick: impl 'a + Send + Sync + Fn(&mut Generator<'_>, &R, &dyn Fn() -> String) -> Mutation<T>,
+) -> Lambda<'a, (), T>
+where
+    R: 'a + Send + Sync + RangeBounds<T> + std::fmt::Debug,
+    T: Target<'a> + PartialOrd,
+{
+    let debug = |b: Bound<&T>| b.map(|x| format!("{:?}", x));
+    let constraint = Constraint::InRange(debug(range.start_bound()), debug(range.end_bound()));
+    lambda_unit(label, move |g, mut t| {
         if !range.contains(&t) {
-            let rand = g.arbitrary(|| {
+            let reason = || {
                 format!(
                     "{}: expected {:?} to be contained in {:?}",
                     context, t, range
                 )
-            })?;
-            t = match (range.start_bound(), range.end_bound()) {
-                (Bound::Unbounded, Bound::Unbounded) => rand,
-                (Bound::Included(a), Bound::Included(b)) if b.clone() - a.clone() >= T::one() => {
-                    a.clone() + rand.rem_euclid(&(b.clone() - a.clone()))
-                }
-                (Bound::Included(a), Bound::Excluded(b)) if b.clone() - a.clone() > T::one() => {
-                    a.clone() + rand.rem_euclid(&(b.clone() - a.clone()))
-                }
-                (Bound::Excluded(a), Bound::Included(b)) if b.clone() - a.clone() > T::one() => {
-                    b.clone() - rand.rem_euclid(&(b.clone() - a.clone()))
-                }
-                (Bound::Unbounded, Bound::Excluded(b)) => {
-                    T::min_value() + rand.rem_euclid(&(b.clone() - T::min_value()))
-                }
-                (Bound::Included(a), Bound::Unbounded) => {
-                    a.clone() + rand.rem_euclid(&(T::max_value() - a.clone()))
-                }
-                _ => panic!("Range not yet supported, sorry! {:?}", range),
             };
+            t = pick(g, &range, &reason)?;
         }
         Ok(t)
     })
+    .describe_with(move |label, ()| {
+        Description::new("fact", label).with_constraint(constraint.clone())
+    })
+}
+
+/// Choose an arbitrary value within the range
+fn arbitrary_in_range<R, T>(
+    g: &mut Generator<'_>,
+    range: &R,
+    reason: &dyn Fn() -> String,
+) -> Mutation<T>
+where
+    R: RangeBounds<T> + std::fmt::Debug,
+    T: PartialOrd
+        + Clone
+        + num::traits::Euclid
+        + std::ops::Add<Output = T>
+        + std::ops::Sub<Output = T>
+        + num::Bounded
+        + num::One
+        + for<'u> arbitrary::Arbitrary<'u>,
+{
+    let rand = g.arbitrary(reason)?;
+    Ok(match (range.start_bound(), range.end_bound()) {
+        (Bound::Unbounded, Bound::Unbounded) => rand,
+        (Bound::Included(a), Bound::Included(b)) if b.clone() - a.clone() >= T::one() => {
+            let span = b.clone() - a.clone();
+            if span < T::max_value() {
+                a.clone() + rand.rem_euclid(&(span + T::one()))
+            } else if range.contains(&rand) {
+                // The range covers the whole type, so there's no
+                // room for `span + 1`
+                rand
+            } else {
+                a.clone() + rand.rem_euclid(&span)
+            }
+        }
+        (Bound::Included(a), Bound::Excluded(b)) if b.clone() - a.clone() > T::one() => {
+            a.clone() + rand.rem_euclid(&(b.clone() - a.clone()))
+        }
+        (Bound::Excluded(a), Bound::Included(b)) if b.clone() - a.clone() > T::one() => {
+            b.clone() - rand.rem_euclid(&(b.clone() - a.clone()))
+        }
+        (Bound::Unbounded, Bound::Excluded(b)) => {
+            T::min_value() + rand.rem_euclid(&(b.clone() - T::min_value()))
+        }
+        (Bound::Included(a), Bound::Unbounded) => {
+            a.clone() + rand.rem_euclid(&(T::max_value() - a.clone()))
+        }
+        _ => panic!("Range not yet supported, sorry! {:?}", range),
+    })
 }
 
 #[test]
+#[cfg(feature = "utils")]
 fn test_in_range() {
     observability::test_run().ok();
     let mut g = utils::random_generator();
@@ -77,3 +179,41 @@ fn test_in_range() {
     nonpositive2.clone().check(&nonpositive2_nums).unwrap();
     assert!(nonpositive1_nums.iter().all(|x| *x <= 0));
 }
+
+#[test]
+#[cfg(feature = "utils")]
+fn test_in_range_inclusive_end() {
+    let mut g = utils::random_generator();
+
+    let fact = in_range("0..=3", 0..=3u8);
+    let xs: Vec<u8> = (0..100)
+        .map(|_| fact.clone().satisfy(&mut g, 200).unwrap())
+        .collect();
+    for x in 0..=3 {
+        assert!(xs.contains(&x));
+    }
+
+    let full = vec(in_range("anything", 0..=u8::MAX));
+    let xs = full.clone().build(&mut g);
+    full.check(&xs).unwrap();
+}
+
+#[test]
+#[cfg(feature = "utils")]
+fn test_in_range_edges() {
+    observability::test_run().ok();
+    let mut g = utils::random_generator();
+
+    let fact = in_range_edges("edgy", -10..100i32, 100);
+    let xs: Vec<i32> = (0..100)
+        .map(|_| fact.clone().satisfy(&mut g, 1000).unwrap())
+        .collect();
+    assert!(xs.iter().all(|x| [-10, -9, 0, 98, 99].contains(x)));
+    for edge in [-10, -9, 0, 98, 99] {
+        assert!(xs.contains(&edge));
+    }
+
+    // Values which are already in range are left alone
+    assert_eq!(fact.clone().satisfy(&mut g, 42).unwrap(), 42);
+    fact.check(&42).unwrap();
+}
@@ -6,6 +6,7 @@ where
     T: Target<'a> + PartialEq + Clone,
 {
     let context = context.to_string();
+    let constraint = Constraint::InSlice(slice.iter().map(|x| format!("{:?}", x)).collect());
     lambda_unit("in_slice", move |g, t| {
         Ok(if !slice.contains(&t) {
             let reason = || {
@@ -19,6 +20,51 @@ where
             t
         })
     })
+    .describe_with(move |label, ()| {
+        Description::new("fact", label).with_constraint(constraint.clone())
+    })
+}
+
+/// Specifies a membership constraint, where during mutation each item is chosen
+/// with a probability proportional to its weight. Items with zero weight are not
+/// members.
+///
+/// ```
+/// # #[cfg(feature = "utils")] {
+/// use contrafact::{*, facts::*};
+///
+/// let mut g = utils::random_generator();
+/// let fact = vec(weighted_slice("payload size", &[(10, 9), (10_000, 1), (99, 0)]));
+/// let sizes = fact.clone().build(&mut g);
+/// fact.check(&sizes).unwrap();
+/// assert!(sizes.iter().all(|s| *s == 10 || *s == 10_000));
+/// # }
+/// ```
+pub fn weighted_slice<'a, T>(context: impl ToString, slice: &'a [(T, u32)]) -> LambdaUnit<'a, T>
+where
+    T: Target<'a> + PartialEq + Clone,
+{
+    let context = context.to_string();
+    let members = || slice.iter().filter(|(_, w)| *w > 0).map(|(t, _)| t);
+    let constraint = Constraint::InSlice(members().map(|x| format!("{:?}", x)).collect());
+    lambda_unit("weighted_slice", move |g, t| {
+        Ok(if !members().any(|m| *m == t) {
+            let reason = || {
+                format!(
+                    "{}: expected {:?} to be contained in {:?}",
+                    context,
+                    t,
+                    members().collect::<Vec<_>>()
+                )
+            };
+            g.choose_weighted(slice, reason)?.to_owned()
+        } else {
+            t
+        })
+    })
+    .describe_with(move |label, ()| {
+        Description::new("fact", label).with_constraint(constraint.clone())
+    })
 }
 
 /// Specifies a membership constraint
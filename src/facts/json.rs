@@ -0,0 +1,663 @@
+//! Facts about JSON data, for constraining values which come from services
+//! that don't have Rust types.
+//!
+//! `serde_json::Value` does not implement `Arbitrary`, so these facts are
+//! written against the [`Json`] wrapper, which does. Data in any other
+//! serde format can be brought into this form via `serde_json::to_value`.
+//!
+//! ```
+//! use contrafact::{*, facts::json::*};
+//! use serde_json::json;
+//!
+//! let fact = facts![
+//!     json_field("name", json_string(eq("alice".to_string()))),
+//!     json_field("age", json_number(0..150)),
+//! ];
+//!
+//! assert!(fact.clone().check(&json!({"name": "alice", "age": 33}).into()).is_ok());
+//! assert!(fact.clone().check(&json!({"name": "alice", "age": -1}).into()).is_err());
+//! assert!(fact.clone().check(&json!({"name": "alice"}).into()).is_err());
+//!
+//! let mut g = utils::random_generator();
+//! let value = fact.build(&mut g);
+//! assert_eq!(value["name"], "alice");
+//! ```
+
+use std::ops::{Bound, RangeBounds};
+
+use arbitrary::{Arbitrary, Unstructured};
+use serde_json::{Map, Number, Value};
+
+use crate::*;
+
+/// A JSON value which can be the target of a Fact.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    derive_more::From,
+    derive_more::Into,
+    derive_more::Deref,
+    derive_more::DerefMut,
+)]
+pub struct Json(pub Value);
+
+/// Arbitrary JSON values will never be nested deeper than this
+const ARBITRARY_DEPTH: usize = 3;
+
+/// Arbitrary JSON arrays and objects will never be longer than this
+const ARBITRARY_LEN: usize = 4;
+
+impl<'a> Arbitrary<'a> for Json {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        arbitrary_value(u, ARBITRARY_DEPTH).map(Json)
+    }
+}
+
+fn arbitrary_value(u: &mut Unstructured, depth: usize) -> arbitrary::Result<Value> {
+    let kinds = if depth == 0 { 3 } else { 5 };
+    Ok(match u.int_in_range(0..=kinds)? {
+        0 => Value::Null,
+        1 => Value::Bool(u.arbitrary()?),
+        2 => Value::Number(u.arbitrary::<i64>()?.into()),
+        3 => Value::String(u.arbitrary()?),
+        4 => {
+            let len = u.int_in_range(0..=ARBITRARY_LEN)?;
+            Value::Array(
+                (0..len)
+                    .map(|_| arbitrary_value(u, depth - 1))
+                    .collect::<arbitrary::Result<_>>()?,
+            )
+        }
+        _ => {
+            let len = u.int_in_range(0..=ARBITRARY_LEN)?;
+            Value::Object(
+                (0..len)
+                    .map(|_| Ok((u.arbitrary()?, arbitrary_value(u, depth - 1)?)))
+                    .collect::<arbitrary::Result<_>>()?,
+            )
+        }
+    })
+}
+
+/// Lifts a Fact about a String into a Fact about a JSON string.
+///
+/// If the value is not a string at all, an arbitrary string is generated
+/// before applying the inner fact.
+pub fn json_string<'a>(inner_fact: impl Fact<'a, String>) -> impl Fact<'a, Json> {
+    lambda("json_string", inner_fact, |g, fact, t: Json| {
+        let s = match t.0 {
+            Value::String(s) => s,
+            other => g.arbitrary(|| format!("expected a JSON string, got {}", other))?,
+        };
+        Ok(Json(Value::String(fact.mutate(g, s)?)))
+    })
+    .label_inner()
+    .snapshot_inner()
+    .stateless_inner()
+}
+
+/// Specifies that a JSON value is an integer within the given range.
+///
+/// An empty range is an error, both when checking and when building.
+pub fn json_number<'a, R>(range: R) -> LambdaUnit<'a, Json>
+where
+    R: 'a + Send + Sync + RangeBounds<i64> + std::fmt::Debug,
+{
+    lambda_unit("json_number", move |g, t: Json| {
+        if let Some(n) = t.as_i64() {
+            if range.contains(&n) {
+                return Ok(t);
+            }
+        }
+        let start = match range.start_bound() {
+            Bound::Included(a) => Some(*a),
+            Bound::Excluded(a) => a.checked_add(1),
+            Bound::Unbounded => Some(i64::MIN),
+        };
+        let end = match range.end_bound() {
+            Bound::Included(b) => Some(*b),
+            Bound::Excluded(b) => b.checked_sub(1),
+            Bound::Unbounded => Some(i64::MAX),
+        };
+        let (start, end) = match (start, end) {
+            (Some(start), Some(end)) if start <= end => (start, end),
+            _ => {
+                return Err(MutationError::User(format!(
+                    "json_number: empty range {:?}",
+                    range
+                )))
+            }
+        };
+        let n = g.int_in_range(start..=end, || {
+            format!("expected a JSON integer in {:?}, got {}", range, t.0)
+        })?;
+        Ok(Json(n.into()))
+    })
+}
+
+/// Lifts a Fact about a JSON value into a Fact about every item of a JSON array.
+///
+/// If the value is not an array at all, an arbitrary array is generated
+/// before applying the inner fact.
+pub fn json_array<'a>(inner_fact: impl Fact<'a, Json>) -> impl Fact<'a, Json> {
+    lambda("json_array", vec(inner_fact), |g, fact, t: Json| {
+        let items = match t.0 {
+            Value::Array(items) => items.into_iter().map(Json).collect(),
+            other => g.arbitrary(|| format!("expected a JSON array, got {}", other))?,
+        };
+        let items = fact.mutate(g, items)?;
+        Ok(Json(Value::Array(items.into_iter().map(|j| j.0).collect())))
+    })
+    .label_inner()
+    .snapshot_inner()
+    .stateless_inner()
+}
+
+/// Lifts a Fact about a JSON value into a Fact about a field of a JSON object.
+///
+/// The field must be present. During mutation, a missing field is populated
+/// with an arbitrary value before applying the inner fact, and a value which
+/// is not an object is replaced by an empty object.
+pub fn json_field<'a>(name: impl ToString, inner_fact: impl Fact<'a, Json>) -> impl Fact<'a, Json> {
+    let name = name.to_string();
+    let description_label = name.clone();
+    lambda(name.clone(), inner_fact, move |g, fact, t: Json| {
+        let mut map = object(g, t)?;
+        if !map.contains_key(&name) {
+            let value: Json =
+                g.arbitrary(|| format!("expected JSON object to have field {:?}", name))?;
+            map.insert(name.clone(), value.0);
+        }
+        let field = map.get_mut(&name).expect("field was just inserted");
+        *field = g.in_path(&name, |g| fact.mutate(g, Json(field.take())))?.0;
+        Ok(Json(Value::Object(map)))
+    })
+    .describe_with(move |_, fact| {
+        Description::new("json_field", &description_label).with_children([fact.describe()])
+    })
+    .snapshot_inner()
+    .stateless_inner()
+}
+
+/// Specifies that a JSON value is an object whose fields satisfy the given facts.
+///
+/// This is the same as combining a [`json_field`] for each field, except that
+/// a non-object value fails only once. Since all of the field facts must be of
+/// the same type, use [`facts!`] with [`json_field`] directly when combining
+/// facts of different types.
+pub fn json_object<'a, S, F>(fields: impl IntoIterator<Item = (S, F)>) -> impl Fact<'a, Json>
+where
+    S: ToString,
+    F: Fact<'a, Json>,
+{
+    let fields: Vec<_> = fields
+        .into_iter()
+        .map(|(name, fact)| json_field(name, fact))
+        .collect();
+    lambda("json_object", fields, |g, fields, t: Json| {
+        let mut t = Json(Value::Object(object(g, t)?));
+        for field in fields.iter_mut() {
+            t = g.accumulate(t, |g, t| field.mutate(g, t))?;
+        }
+        Ok(t)
+    })
+    .describe_with(|label, fields| {
+        Description::new("json_object", label).with_children(fields.iter().map(|f| f.describe()))
+    })
+    .label_with(|label, fields| {
+        let fields: Vec<_> = fields.iter().map(|f| f.label()).collect();
+        label_children(label, &fields)
+    })
+    .snapshot_each()
+    .stateless_with(|fields| fields.iter().all(|f| f.is_stateless()))
+}
+
+/// Build a Fact from a subset of [JSON Schema](https://json-schema.org/).
+///
+/// The supported keywords are:
+/// - `type`, as a single type name
+/// - `properties` and `required` for objects. Properties which are not
+///   required are only checked when present.
+/// - `items`, `minItems` and `maxItems` for arrays
+/// - `minLength` and `maxLength` for strings
+/// - `minimum` and `maximum` for numbers. `"integer"` values are checked and
+///   generated as integers, and `"number"` values as floats.
+/// - `enum` and `const`
+///
+/// Any other keyword is ignored.
+///
+/// ```
+/// use contrafact::{*, facts::json::*};
+/// use serde_json::json;
+///
+/// let fact = json_schema(&json!({
+///     "type": "object",
+///     "properties": {
+///         "id": {"type": "integer", "minimum": 1},
+///         "tags": {"type": "array", "items": {"enum": ["a", "b"]}, "maxItems": 3},
+///     },
+///     "required": ["id", "tags"],
+/// }))
+/// .unwrap();
+///
+/// assert!(fact.clone().check(&json!({"id": 4, "tags": ["a"]}).into()).is_ok());
+/// assert!(fact.clone().check(&json!({"id": 4, "tags": ["c"]}).into()).is_err());
+///
+/// let mut g = utils::random_generator();
+/// let value = fact.build(&mut g);
+/// assert!(value["id"].as_i64().unwrap() >= 1);
+/// ```
+pub fn json_schema<'a>(schema: &Value) -> ContrafactResult<impl Fact<'a, Json>> {
+    schema_fact(schema)
+}
+
+fn schema_fact<'a>(schema: &Value) -> ContrafactResult<LambdaUnit<'a, Json>> {
+    let schema = match schema {
+        Value::Bool(true) => return Ok(always()),
+        Value::Bool(false) => return Ok(never("schema is `false`")),
+        Value::Object(schema) => schema,
+        other => return Err(format!("invalid JSON schema: {}", other).into()),
+    };
+
+    let mut facts: Vec<LambdaUnit<'a, Json>> = vec![];
+
+    if let Some(value) = schema.get("const") {
+        facts.push(eq(Json(value.clone())));
+    }
+
+    if let Some(values) = schema.get("enum") {
+        let values = values
+            .as_array()
+            .filter(|vs| !vs.is_empty())
+            .ok_or_else(|| format!("`enum` must be a non-empty array, got {}", values))?;
+        facts.push(json_enum(values.clone()));
+    }
+
+    match schema.get("type") {
+        None => (),
+        Some(Value::String(ty)) => match ty.as_str() {
+            "null" => facts.push(eq(Json(Value::Null))),
+            "boolean" => facts.push(json_bool()),
+            "string" => {
+                let min = usize_keyword(schema, "minLength")?.unwrap_or(0);
+                let max = usize_keyword(schema, "maxLength")?;
+                facts.push(erased(json_string(string_length(min, max))));
+            }
+            "integer" => {
+                let min = bound_keyword(schema, "minimum")?;
+                let max = bound_keyword(schema, "maximum")?;
+                if let (Bound::Included(min), Bound::Included(max)) = (min, max) {
+                    check_bounds(min, max)?;
+                }
+                facts.push(json_number((min, max)));
+            }
+            "number" => {
+                let min = float_keyword(schema, "minimum")?;
+                let max = float_keyword(schema, "maximum")?;
+                if let (Some(min), Some(max)) = (min, max) {
+                    check_bounds(min, max)?;
+                }
+                facts.push(json_float(min, max));
+            }
+            "array" => {
+                let items = match schema.get("items") {
+                    Some(items) => schema_fact(items)?,
+                    None => always(),
+                };
+                let min = usize_keyword(schema, "minItems")?.unwrap_or(0);
+                let max = usize_keyword(schema, "maxItems")?;
+                facts.push(erased(json_array(items)));
+                facts.push(array_length(min, max));
+            }
+            "object" => {
+                let required: Vec<&str> = match schema.get("required") {
+                    Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).collect(),
+                    Some(other) => return Err(format!("invalid `required`: {}", other).into()),
+                    None => vec![],
+                };
+                let empty = Map::new();
+                let properties = match schema.get("properties") {
+                    Some(Value::Object(properties)) => properties,
+                    Some(other) => return Err(format!("invalid `properties`: {}", other).into()),
+                    None => &empty,
+                };
+                facts.push(lambda_unit("json_object", |g, t: Json| {
+                    Ok(Json(Value::Object(object(g, t)?)))
+                }));
+                for name in required.iter() {
+                    let fact = match properties.get(*name) {
+                        Some(property) => schema_fact(property)?,
+                        None => always(),
+                    };
+                    facts.push(erased(json_field(name.to_string(), fact)));
+                }
+                for (name, property) in properties {
+                    if !required.contains(&name.as_str()) {
+                        facts.push(json_optional_field(name.clone(), schema_fact(property)?));
+                    }
+                }
+            }
+            other => return Err(format!("unsupported JSON schema type: {:?}", other).into()),
+        },
+        Some(other) => return Err(format!("unsupported JSON schema type: {}", other).into()),
+    }
+
+    Ok(erased(
+        lambda("json_schema", facts, |g, facts, mut t| {
+            for fact in facts.iter_mut() {
+                t = fact.mutate(g, t)?;
+            }
+            Ok(t)
+        })
+        .label_with(|label, facts| {
+            let facts: Vec<_> = facts.iter().map(|f| f.label()).collect();
+            label_children(label, &facts)
+        })
+        .snapshot_each(),
+    ))
+}
+
+/// Erase the type of a stateless fact, so that facts built from a schema
+/// can be collected together.
+fn erased<'a>(fact: impl 'a + Fact<'a, Json>) -> LambdaUnit<'a, Json> {
+    lambda_unit(fact.label(), move |g, t| fact.clone().mutate(g, t))
+}
+
+fn object(g: &mut Generator, t: Json) -> Mutation<Map<String, Value>> {
+    match t.0 {
+        Value::Object(map) => Ok(map),
+        other => {
+            g.fail_with(|| format!("expected a JSON object, got {}", other))?;
+            Ok(Map::new())
+        }
+    }
+}
+
+fn usize_keyword(schema: &Map<String, Value>, keyword: &str) -> ContrafactResult<Option<usize>> {
+    schema
+        .get(keyword)
+        .map(|v| {
+            v.as_u64().map(|n| n as usize).ok_or_else(|| {
+                format!("`{}` must be a non-negative integer, got {}", keyword, v).into()
+            })
+        })
+        .transpose()
+}
+
+fn bound_keyword(schema: &Map<String, Value>, keyword: &str) -> ContrafactResult<Bound<i64>> {
+    match schema.get(keyword) {
+        None => Ok(Bound::Unbounded),
+        Some(v) => v
+            .as_i64()
+            .map(Bound::Included)
+            .ok_or_else(|| format!("`{}` must be an integer, got {}", keyword, v).into()),
+    }
+}
+
+fn float_keyword(schema: &Map<String, Value>, keyword: &str) -> ContrafactResult<Option<f64>> {
+    match schema.get(keyword) {
+        None => Ok(None),
+        Some(v) => v
+            .as_f64()
+            .map(Some)
+            .ok_or_else(|| format!("`{}` must be a number, got {}", keyword, v).into()),
+    }
+}
+
+/// A `minimum` above the `maximum` leaves no valid values
+fn check_bounds<N: PartialOrd + std::fmt::Display>(min: N, max: N) -> ContrafactResult<()> {
+    if min > max {
+        return Err(format!("`minimum` {} is greater than `maximum` {}", min, max).into());
+    }
+    Ok(())
+}
+
+fn json_enum<'a>(values: Vec<Value>) -> LambdaUnit<'a, Json> {
+    lambda_unit("json_enum", move |g, t: Json| {
+        if values.contains(&t.0) {
+            return Ok(t);
+        }
+        let i = g.int_in_range(0..=values.len() - 1, || {
+            format!("expected {} to be one of {:?}", t.0, values)
+        })?;
+        Ok(Json(values[i].clone()))
+    })
+}
+
+fn json_bool<'a>() -> LambdaUnit<'a, Json> {
+    lambda_unit("json_bool", |g, t: Json| {
+        if t.is_boolean() {
+            return Ok(t);
+        }
+        let b: bool = g.arbitrary(|| format!("expected a JSON boolean, got {}", t.0))?;
+        Ok(Json(b.into()))
+    })
+}
+
+/// How far from a single bound, or from zero, to generate floats which are
+/// unbounded on one side or both
+const FLOAT_SPAN: f64 = i64::MAX as f64;
+
+fn json_float<'a>(min: Option<f64>, max: Option<f64>) -> LambdaUnit<'a, Json> {
+    lambda_unit("json_float", move |g, t: Json| {
+        if let Some(n) = t.as_f64() {
+            if min.is_none_or(|min| n >= min) && max.is_none_or(|max| n <= max) {
+                return Ok(t);
+            }
+        }
+        let (lo, hi) = match (min, max) {
+            (Some(lo), Some(hi)) if lo <= hi => (lo, hi),
+            (Some(_), Some(_)) => {
+                return Err(MutationError::User(format!(
+                    "json_float: empty range {:?} to {:?}",
+                    min, max
+                )))
+            }
+            (Some(lo), None) => (lo, lo + FLOAT_SPAN),
+            (None, Some(hi)) => (hi - FLOAT_SPAN, hi),
+            (None, None) => (-FLOAT_SPAN, FLOAT_SPAN),
+        };
+        let x = g.int_in_range(0..=u64::MAX, || {
+            format!(
+                "expected a JSON number between {:?} and {:?}, got {}",
+                min, max, t.0
+            )
+        })?;
+        let frac = x as f64 / u64::MAX as f64;
+        let n = (lo * (1.0 - frac) + hi * frac).clamp(lo, hi);
+        let n = Number::from_f64(n).ok_or_else(|| {
+            MutationError::User(format!("json_float: {} is not a JSON number", n))
+        })?;
+        Ok(Json(Value::Number(n)))
+    })
+}
+
+fn string_length<'a>(min: usize, max: Option<usize>) -> LambdaUnit<'a, String> {
+    lambda_unit("string_length", move |g, mut s: String| {
+        let len = s.chars().count();
+        if len < min || max.is_some_and(|max| len > max) {
+            g.fail_with(|| {
+                format!(
+                    "expected string length between {} and {:?}, got {:?}",
+                    min, max, s
+                )
+            })?;
+            s = s.chars().take(max.unwrap_or(len)).collect();
+            while s.chars().count() < min {
+                s.push(g.arbitrary(|| "string too short")?);
+            }
+        }
+        Ok(s)
+    })
+}
+
+fn array_length<'a>(min: usize, max: Option<usize>) -> LambdaUnit<'a, Json> {
+    lambda_unit("array_length", move |g, mut t: Json| {
+        if let Value::Array(items) = &mut t.0 {
+            if items.len() < min || max.is_some_and(|max| items.len() > max) {
+                g.fail_with(|| {
+                    format!(
+                        "expected array length between {} and {:?}, got {}",
+                        min,
+                        max,
+                        items.len()
+                    )
+                })?;
+                items.truncate(max.unwrap_or(items.len()));
+                while items.len() < min {
+                    items.push(g.arbitrary::<Json, _>(|| "array too short")?.0);
+                }
+            }
+        }
+        Ok(t)
+    })
+}
+
+fn json_optional_field<'a>(name: String, fact: LambdaUnit<'a, Json>) -> LambdaUnit<'a, Json> {
+    lambda_unit(name.clone(), move |g, mut t: Json| {
+        if let Some(field) = t.get_mut(&name) {
+            *field = g
+                .in_path(&name, |g| fact.clone().mutate(g, Json(field.take())))?
+                .0;
+        }
+        Ok(t)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_object() {
+        observability::test_run().ok();
+        let mut g = utils::random_generator();
+
+        let f = || {
+            vec(json_object(vec![
+                ("a", erased(json_number(1..=3))),
+                ("b", erased(json_string(eq("x".to_string())))),
+            ]))
+        };
+
+        let items = f().build(&mut g);
+        f().check(&items).unwrap();
+        for item in items {
+            assert!((1..=3).contains(&item["a"].as_i64().unwrap()));
+            assert_eq!(item["b"], "x");
+        }
+
+        assert!(f().check(&vec![json!({"a": 2, "b": "y"}).into()]).is_err());
+        assert!(f().check(&vec![json!({"a": 2}).into()]).is_err());
+        assert!(f().check(&vec![json!([]).into()]).is_err());
+    }
+
+    #[test]
+    fn test_json_combinators() {
+        observability::test_run().ok();
+        let mut g = utils::random_generator();
+
+        let f = || json_array(or(json_number(0..10), json_string(always())));
+        let value = f().build(&mut g);
+        f().check(&value).unwrap();
+        assert!(value
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|v| v.is_string() || (0..10).contains(&v.as_i64().unwrap())));
+
+        let f = || vec(not(json_number(..)));
+        let values = f().build(&mut g);
+        f().check(&values).unwrap();
+        assert!(values.iter().all(|v| !v.is_i64()));
+    }
+
+    #[test]
+    fn test_json_schema() {
+        observability::test_run().ok();
+        let mut g = utils::random_generator();
+
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "id": {"type": "integer", "minimum": 1, "maximum": 10},
+                "name": {"type": "string", "minLength": 2, "maxLength": 4},
+                "score": {"type": "number", "minimum": 0.5},
+                "kind": {"const": "user"},
+                "tags": {
+                    "type": "array",
+                    "items": {"enum": ["a", "b", null]},
+                    "minItems": 1,
+                    "maxItems": 3,
+                },
+                "nested": {
+                    "type": "object",
+                    "properties": {"ok": {"type": "boolean"}},
+                    "required": ["ok"],
+                },
+            },
+            "required": ["id", "name", "kind", "tags", "nested"],
+        });
+        let f = json_schema(&schema).unwrap();
+
+        let values = vec(f.clone()).build(&mut g);
+        vec(f.clone()).check(&values).unwrap();
+        for v in values {
+            assert!((1..=10).contains(&v["id"].as_i64().unwrap()));
+            assert!((2..=4).contains(&v["name"].as_str().unwrap().chars().count()));
+            assert_eq!(v["kind"], "user");
+            assert!((1..=3).contains(&v["tags"].as_array().unwrap().len()));
+            assert!(v["nested"]["ok"].is_boolean());
+            if let Some(score) = v.get("score") {
+                assert!(score.as_f64().unwrap() >= 0.5);
+            }
+        }
+
+        let valid = json!({
+            "id": 3,
+            "name": "abc",
+            "score": 0.75,
+            "kind": "user",
+            "tags": [null],
+            "nested": {"ok": true},
+        });
+        f.clone().check(&valid.clone().into()).unwrap();
+
+        let mut invalid = valid.clone();
+        invalid["score"] = json!(0.25);
+        assert!(f.clone().check(&invalid.into()).is_err());
+
+        let mut invalid = valid;
+        invalid["nested"] = json!({});
+        assert!(f.check(&invalid.into()).is_err());
+
+        assert!(json_schema(&json!({"type": "date"})).is_err());
+        assert!(json_schema(&json!({"enum": []})).is_err());
+        assert!(json_schema(&json!({"type": "integer", "minimum": 5, "maximum": 4})).is_err());
+        assert!(json_schema(&json!({"type": "number", "minimum": 0.8, "maximum": 0.2})).is_err());
+    }
+
+    #[test]
+    fn test_json_float_without_integers() {
+        let mut g = utils::random_generator();
+        let f = json_schema(&json!({"type": "number", "minimum": 0.2, "maximum": 0.8})).unwrap();
+
+        let values = vec(f.clone()).build(&mut g);
+        vec(f).check(&values).unwrap();
+        assert!(values
+            .iter()
+            .all(|v| (0.2..=0.8).contains(&v.as_f64().unwrap())));
+    }
+
+    #[test]
+    fn test_json_number_empty_range() {
+        let mut g = utils::random_generator();
+        assert!(json_number(5..5).build_fallible(&mut g).is_err());
+        assert!(matches!(
+            json_number(5..5).check(&json!(5).into()),
+            Check::Error(_)
+        ));
+    }
+}
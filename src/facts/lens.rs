@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use crate::*;
 
 /// Lifts a Fact about a subset of some data into a Fact about the superset,
@@ -14,6 +16,7 @@ use crate::*;
 /// We may consider using a true lens library for this in the future.
 ///
 /// ```
+/// # #[cfg(feature = "utils")] {
 /// use contrafact::*;
 /// use arbitrary::*;
 ///
@@ -31,6 +34,7 @@ use crate::*;
 /// let mut g = utils::random_generator();
 /// let a = fact.build(&mut g);
 /// assert_eq!(a.x, 1);
+/// # }
 /// ```
 //
 // TODO: can rewrite this in terms of PrismFact for DRYness
@@ -44,14 +48,19 @@ where
     T: Target<'a>,
     L: 'a + Clone + Send + Sync + Fn(&mut O) -> &mut T,
 {
+    let label = label.to_string();
     let accessor2 = accessor.clone();
+    let accessor3 = accessor.clone();
     let getter = move |mut o| accessor(&mut o).clone();
     let setter = move |mut o, t: T| {
         let r = accessor2(&mut o);
         *r = t;
         o
     };
-    lens2(label, getter, setter, inner_fact).labeled("lens1")
+    lens(label.clone(), getter, setter, inner_fact).check_mut_with(move |fact, o| {
+        fact.check_mut(accessor3(o))
+            .map(|err| format!("{}: {}", label, err))
+    })
 }
 
 /// Lifts a Fact about a subset of some data into a Fact about the superset, using
@@ -61,6 +70,9 @@ where
 /// when the setter requires modifications other than replacing the item specified
 /// by the getter, for instance if your data contains some kind of digest of the data
 /// being focused on, then the digest must also be recomputed when the focus is modified.
+///
+/// The getter takes the whole value, so every check clones it. [`lens1`] reads
+/// through a reference instead, and should be preferred where it's possible.
 pub fn lens2<'a, O, T>(
     label: impl ToString,
     getter: impl 'a + Clone + Send + Sync + Fn(O) -> T,
@@ -71,17 +83,37 @@ where
     O: Target<'a>,
     T: Target<'a>,
 {
-    let label = label.to_string();
-    lambda("lens", inner_fact, move |g, fact, o: O| {
+    lens(label, getter, setter, inner_fact)
+}
+
+/// The lens behind both [`lens1`] and [`lens2`]
+fn lens<'a, O, T, F>(
+    label: impl ToString,
+    getter: impl 'a + Clone + Send + Sync + Fn(O) -> T,
+    setter: impl 'a + Clone + Send + Sync + Fn(O, T) -> O,
+    inner_fact: F,
+) -> Lambda<'a, F, O>
+where
+    O: Target<'a>,
+    T: Target<'a>,
+    F: Fact<'a, T>,
+{
+    let label: Arc<str> = label.to_string().into();
+    let description_label = label.clone();
+    lambda(label.clone(), inner_fact, move |g, fact, o: O| {
         let t = getter(o.clone());
-        let t = fact
-            .mutate(g, t)
-            .map_check_err(|err| format!("lens1({}) > {}", label, err))?;
+        let t = g.in_label(&label, |g| fact.mutate(g, t))?;
         Ok(setter(o, t))
     })
+    .describe_with(move |_, fact| {
+        Description::new("lens", &description_label).with_children([fact.describe()])
+    })
+    .snapshot_inner()
+    .stateless_inner()
 }
 
 #[cfg(test)]
+#[cfg(feature = "utils")]
 mod tests {
     use super::*;
     use crate::facts::*;
@@ -104,4 +136,140 @@ mod tests {
 
         assert!(ones.iter().all(|s| s.x == 1));
     }
+
+    /// Records the label, path and mode of every span a lambda emits
+    struct Spans(std::sync::Mutex<Vec<Vec<String>>>);
+
+    /// Collects the fields of a span
+    #[derive(Default)]
+    struct Fields(Vec<String>);
+
+    impl tracing::field::Visit for Fields {
+        fn record_str(&mut self, _: &tracing::field::Field, value: &str) {
+            self.0.push(value.to_string());
+        }
+
+        fn record_debug(&mut self, _: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0.push(format!("{:?}", value));
+        }
+    }
+
+    impl tracing::Subscriber for &'static Spans {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            let mut spans = self.0.lock().unwrap();
+            if span.metadata().name() == "mutate" {
+                let mut fields = Fields::default();
+                span.record(&mut fields);
+                spans.push(fields.0);
+            }
+            tracing::span::Id::from_u64(spans.len() as u64 + 1)
+        }
+
+        fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+
+        fn event(&self, _: &tracing::Event<'_>) {}
+
+        fn enter(&self, _: &tracing::span::Id) {}
+
+        fn exit(&self, _: &tracing::span::Id) {}
+    }
+
+    #[test]
+    fn test_path() {
+        let spans: &'static Spans = Box::leak(Box::new(Spans(Default::default())));
+        let f = vec(lens1("S::y", |s: &mut S| &mut s.y, eq(1)));
+        assert!(f.is_stateless());
+
+        // Stateless facts are checked through references, except while tracing,
+        // so that every fact still emits its span with its path
+        let check = tracing::subscriber::with_default(spans, || {
+            f.check(&vec![S { x: 0, y: 1 }, S { x: 1, y: 2 }])
+        });
+        assert_eq!(
+            check.failures().unwrap(),
+            &["[1]: S::y: expected 2 == 1".to_string()]
+        );
+        assert_eq!(
+            *spans.0.lock().unwrap(),
+            [
+                ["vec", "", "check"],
+                ["S::y", "[0]", "check"],
+                ["eq(1)", "[0]/S::y", "check"],
+                ["S::y", "[1]", "check"],
+                ["eq(1)", "[1]/S::y", "check"],
+            ]
+        );
+    }
+
+    thread_local! {
+        static CLONES: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    /// Counts how many times it's cloned
+    #[derive(Debug, PartialEq)]
+    struct Counted(u32);
+
+    impl Clone for Counted {
+        fn clone(&self) -> Self {
+            CLONES.with(|c| c.set(c.get() + 1));
+            Self(self.0)
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Big {
+        x: u32,
+        c: Counted,
+    }
+
+    #[test]
+    fn test_check_mut_doesnt_clone() {
+        let f = vec(lens1("Big::x", |b: &mut Big| &mut b.x, eq(1)));
+        let mut bigs: Vec<Big> = (0..10)
+            .map(|i| Big {
+                x: 1,
+                c: Counted(i),
+            })
+            .collect();
+
+        CLONES.with(|c| c.set(0));
+        f.check_mut(&mut bigs).unwrap();
+        assert_eq!(CLONES.with(|c| c.get()), 0);
+
+        bigs[3].x = 2;
+        assert_eq!(
+            f.check_mut(&mut bigs).failures().unwrap(),
+            &["[3]: Big::x: expected 2 == 1".to_string()]
+        );
+        // The failures are the same as those of a check by mutation
+        let mut g = Generator::checker();
+        let result = f.clone().mutate(&mut g, bigs.clone());
+        assert_eq!(g.into_check(result), f.check_mut(&mut bigs));
+    }
+
+    #[test]
+    fn test_lens2_check_clones() {
+        let big = Big {
+            x: 1,
+            c: Counted(0),
+        };
+
+        // Once to borrow the value in `check`, and once for the getter
+        let f = lens2("Big::x", |b: Big| b.x, |b, x| Big { x, ..b }, eq(1));
+        CLONES.with(|c| c.set(0));
+        f.check(&big).unwrap();
+        assert_eq!(CLONES.with(|c| c.get()), 2);
+
+        // Only once, to borrow the value in `check`
+        let f = lens1("Big::x", |b: &mut Big| &mut b.x, eq(1));
+        CLONES.with(|c| c.set(0));
+        f.check(&big).unwrap();
+        assert_eq!(CLONES.with(|c| c.get()), 1);
+    }
 }
@@ -1,18 +1,21 @@
 use super::*;
 
 /// Negates a fact
-// TODO: `not` in particular would really benefit from Facts having accessible
-// labels, since currently you can only get context about why a `not` fact passed,
-// not why it fails.
 pub fn not<'a, T>(fact: LambdaUnit<'a, T>) -> LambdaUnit<'a, T>
 where
-    T: Target<'a>,
+    T: Target<'a> + for<'u> arbitrary::Arbitrary<'u>,
 {
+    let inner = fact.clone();
+    let inner_label = format!("not/{}", fact.label());
+    let brute_label = inner_label.clone();
     lambda_unit("not", move |g, t| {
-        let label = format!("not({:?})", fact);
         let fact = fact.clone();
-        brute(label, move |o| fact.clone().check(o).is_err()).mutate(g, t)
+        brute(&brute_label, move |o| fact.clone().check(o).is_err()).mutate(g, t)
     })
+    .describe_with(move |label, ()| {
+        Description::new("not", label).with_children([inner.describe()])
+    })
+    .label_with(move |_, ()| inner_label.clone())
 }
 
 // /// Negates a fact, with no context given
@@ -25,6 +28,7 @@ where
 // }
 
 #[test]
+#[cfg(feature = "utils")]
 fn test_not() {
     observability::test_run().ok();
     let mut g = utils::random_generator();
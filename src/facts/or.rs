@@ -6,19 +6,32 @@ where
     T: Target<'a>,
 {
     lambda("or", (a, b), |g, (a, b), t| {
-        use rand::{thread_rng, Rng};
-
-        let a_ok = a.clone().check(&t).is_ok();
-        let b_ok = b.clone().check(&t).is_ok();
+        let a_ok = g.probe(a, &t);
+        let b_ok = !a_ok && g.probe(b, &t);
+        let name = || format!("or({}, {})", a.label(), b.label());
         match (a_ok, b_ok) {
-            (true, _) => Ok(t),
-            (_, true) => Ok(t),
+            (true, _) => {
+                g.cover("or", name, &["left", "right"], 0);
+                Ok(t)
+            }
+            (_, true) => {
+                g.cover("or", name, &["left", "right"], 1);
+                Ok(t)
+            }
             (false, false) => {
-                g.fail(format!(
-                    "expected either one of the following conditions to be met: {:?} OR {:?}",
-                    a, b
-                ))?;
-                if thread_rng().gen::<bool>() {
+                let reason = || {
+                    format!(
+                        "expected either one of the following conditions to be met: {:?} OR {:?}",
+                        a, b
+                    )
+                };
+                g.fail_with(reason)?;
+                // Head for whichever side has been reached less, if coverage is being tracked
+                let branch = match g.least_covered(name) {
+                    Some(branch) => branch,
+                    None => g.int_in_range(0..=1u8, reason)? as usize,
+                };
+                if branch == 0 {
                     a.mutate(g, t)
                 } else {
                     b.mutate(g, t)
@@ -26,9 +39,16 @@ where
             }
         }
     })
+    .describe_with(|label, (a, b)| {
+        Description::new("or", label).with_children([a.describe(), b.describe()])
+    })
+    .label_with(|label, (a, b)| label_children(label, &[a.label(), b.label()]))
+    .snapshot_pair()
+    .stateless_with(|(a, b)| a.is_stateless() && b.is_stateless())
 }
 
 #[test]
+#[cfg(feature = "utils")]
 fn test_or() {
     observability::test_run().ok();
     let mut g = utils::random_generator();
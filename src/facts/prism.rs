@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use crate::*;
 
 /// Lifts a Fact about some *optional* subset of data into a Fact about the
@@ -16,6 +18,7 @@ use crate::*;
 /// be present.
 ///
 /// ```
+/// # #[cfg(feature = "utils")] {
 /// use contrafact::*;
 /// use arbitrary::{Arbitrary, Unstructured};
 ///
@@ -52,6 +55,7 @@ use crate::*;
 ///     E::X(x) => assert_eq!(x, 1),
 ///     _ => (),  // Y is not defined by the prism, so it can take on any value.
 /// };
+/// # }
 /// ```
 ///
 /// The `prism` closure is a rather lazy way to provide a prism in the
@@ -67,18 +71,39 @@ where
     T: Target<'a>,
     P: 'a + Send + Sync + Fn(&mut O) -> Option<&mut T>,
 {
-    let label = label.to_string();
-    lambda("prism", inner_fact, move |g, fact, mut t| {
-        if let Some(t) = prism(&mut t) {
-            *t = fact
-                .mutate(g, t.clone())
-                .map_check_err(|err| format!("prism({}) > {}", label, err))?;
+    let label: Arc<str> = label.to_string().into();
+    let description_label = label.clone();
+    let check_label = label.clone();
+    let prism = Arc::new(prism);
+    let check_prism = prism.clone();
+    lambda(label.clone(), inner_fact, move |g, fact, mut t| {
+        let matched = prism(&mut t);
+        g.cover(
+            "prism",
+            || label.to_string(),
+            &["None", "Some"],
+            matched.is_some() as usize,
+        );
+        if let Some(t) = matched {
+            *t = g.in_label(&label, |g| fact.mutate(g, t.clone()))?;
         }
         Ok(t)
     })
+    .describe_with(move |_, fact| {
+        Description::new("prism", &description_label).with_children([fact.describe()])
+    })
+    .check_mut_with(move |fact, o| match check_prism(o) {
+        Some(t) => fact
+            .check_mut(t)
+            .map(|err| format!("{}: {}", check_label, err)),
+        None => Check::pass(),
+    })
+    .snapshot_inner()
+    .stateless_inner()
 }
 
 #[cfg(test)]
+#[cfg(feature = "utils")]
 mod tests {
     use super::*;
     use crate::utils;
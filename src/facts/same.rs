@@ -1,4 +1,6 @@
-use super::{brute::brute_labeled, *};
+#[cfg(feature = "arbitrary")]
+use super::brute::brute_labeled;
+use super::*;
 
 /// Specifies an equality constraint between two items in a tuple
 pub fn same<'a, T>() -> LambdaUnit<'a, (T, T)>
@@ -14,9 +16,10 @@ where
 }
 
 /// Specifies an inequality constraint between two items in a tuple
+#[cfg(feature = "arbitrary")]
 pub fn different<'a, T>() -> LambdaUnit<'a, (T, T)>
 where
-    T: Target<'a> + PartialEq,
+    T: Target<'a> + PartialEq + for<'u> arbitrary::Arbitrary<'u>,
 {
     brute_labeled(|(a, b)| {
         if a == b {
@@ -31,6 +34,7 @@ where
 }
 
 #[test]
+#[cfg(feature = "utils")]
 fn test_same() {
     observability::test_run().ok();
     let mut g = utils::random_generator();
@@ -7,12 +7,14 @@
 
 use crate::*;
 
+#[cfg(feature = "arbitrary")]
 use super::and;
 
 /// Lifts a Fact about an item in a Vec into a fact about the whole Vec.
 ///
 ///
 /// ```
+/// # #[cfg(feature = "utils")] {
 /// use contrafact::{*, facts::*};
 ///
 /// let mut g = utils::random_generator();
@@ -21,11 +23,13 @@ use super::and;
 /// let fact = facts::vec(facts::eq(1));
 /// let list = fact.clone().satisfy(&mut g, vec![0; 5]).unwrap();
 /// assert_eq!(list, vec![1, 1, 1, 1, 1]);
+/// # }
 /// ```
 ///
 /// When using a Fact which modifies its state,
 ///
 /// ```
+/// # #[cfg(feature = "utils")] {
 /// use contrafact::{*, facts::*};
 ///
 /// let mut g = utils::random_generator();
@@ -34,36 +38,136 @@ use super::and;
 /// let fact = vec(consecutive_int_(0));
 /// let list = fact.clone().satisfy(&mut g, vec![0; 5]).unwrap();
 /// assert_eq!(list, vec![0, 1, 2, 3, 4]);
+/// # }
 /// ```
 pub fn vec<'a, T>(inner_fact: impl Fact<'a, T>) -> impl Fact<'a, Vec<T>>
 where
     T: Target<'a> + Clone,
 {
-    lambda("vec", inner_fact, |g, f, t: Vec<T>| {
-        t.into_iter()
-            .enumerate()
-            .map(|(i, o)| {
-                f.mutate(g, o)
-                    .map_check_err(|e| format!("seq[{}]: {}", i, e))
-            })
-            .collect::<Result<Vec<_>, _>>()
+    lambda("vec", inner_fact, |g, f, t: Vec<T>| mutate_items(g, f, t))
+        .describe_with(|label, f| Description::new("vec", label).with_children([f.describe()]))
+        .label_inner()
+        .check_mut_with(|f, t| {
+            t.iter_mut()
+                .enumerate()
+                .fold(Check::pass(), |check, (i, o)| {
+                    check.and_then(|| f.check_mut(o).map(|e| format!("[{}]: {}", i, e)))
+                })
+        })
+        .snapshot_inner()
+        .stateless_inner()
+}
+
+/// Like [`vec()`], except that when checking with a stateless inner fact
+/// (see [`Fact::is_stateless`]), the items are checked in parallel.
+/// Stateful facts are checked in order, as by `vec`.
+///
+/// Either way, the failures are the same as `vec` would report, in the order
+/// of the items they're about.
+///
+/// ```
+/// use contrafact::{*, facts::*};
+///
+/// let fact = par_vec(in_range("small", 0..10u32));
+/// let failures = fact.check(&(0..100_000).collect()).result().unwrap().unwrap_err();
+/// assert_eq!(failures.len(), 99_990);
+/// assert!(failures[0].starts_with("[10]: "));
+/// assert!(failures[99_989].starts_with("[99999]: "));
+/// ```
+#[cfg(feature = "rayon")]
+pub fn par_vec<'a, T>(inner_fact: impl Fact<'a, T>) -> impl Fact<'a, Vec<T>>
+where
+    T: Target<'a> + Clone,
+{
+    lambda("par_vec", inner_fact, |g, f, mut t: Vec<T>| {
+        if !g.is_checking() || !f.is_stateless() || g.coverage().is_some() {
+            return mutate_items(g, f, t);
+        }
+        let checks: Vec<Check> = if g.is_labeled() {
+            // The nearest label goes before the rest of the path, so each item is
+            // checked by mutation from here, with a clone of the fact per task
+            use rayon::prelude::*;
+
+            let checker = g.checkers();
+            t.par_iter()
+                .enumerate()
+                .map_init(
+                    || f.clone(),
+                    |f, (i, o)| {
+                        let mut checker = checker();
+                        let result = checker.in_index(i, |g| f.mutate(g, o.clone()));
+                        checker.into_check(result)
+                    },
+                )
+                .collect()
+        } else {
+            let prefix = g.prefix();
+            par_check_items(f, &mut t)
+                .into_iter()
+                .map(|check| check.map(|e| format!("{}{}", prefix, e)))
+                .collect()
+        };
+        for check in checks {
+            g.absorb(check)?;
+        }
+        Ok(t)
+    })
+    .describe_with(|label, f| Description::new("vec", label).with_children([f.describe()]))
+    .check_mut_with(|f, t| {
+        par_check_items(f, t)
+            .into_iter()
+            .fold(Check::pass(), |check, next| check.and_then(|| next))
     })
+    .label_inner()
+    .snapshot_inner()
+    .stateless_inner()
+}
+
+/// Check each item in parallel by reference, prefixing each failure with the
+/// index of its item. The inner fact must be stateless.
+#[cfg(feature = "rayon")]
+fn par_check_items<'a, T>(f: &impl Fact<'a, T>, items: &mut [T]) -> Vec<Check>
+where
+    T: Target<'a>,
+{
+    use rayon::prelude::*;
+
+    items
+        .par_iter_mut()
+        .enumerate()
+        .map(|(i, o)| f.check_mut(o).map(|e| format!("[{}]: {}", i, e)))
+        .collect()
+}
+
+/// Apply a fact to each item in turn, carrying its state from one item to the next
+fn mutate_items<'a, T>(
+    g: &mut Generator<'_>,
+    f: &mut impl Fact<'a, T>,
+    t: Vec<T>,
+) -> Mutation<Vec<T>>
+where
+    T: Target<'a>,
+{
+    t.into_iter()
+        .enumerate()
+        .map(|(i, o)| g.accumulate(o, |g, o| g.in_index(i, |g| f.mutate(g, o))))
+        .collect::<Result<Vec<_>, _>>()
 }
 
 /// Checks that a Vec is of a given length
+#[cfg(feature = "arbitrary")]
 pub fn vec_len<'a, T>(len: usize) -> LambdaUnit<'a, Vec<T>>
 where
-    T: Target<'a> + Clone + 'a,
+    T: Target<'a> + Clone + 'a + for<'u> arbitrary::Arbitrary<'u>,
 {
     lambda_unit("vec_len", move |g, mut t: Vec<T>| {
-        if t.len() > len {
-            g.fail(format!(
+        let actual = t.len();
+        g.truncate(&mut t, len, || {
+            format!(
                 "vec should be of length {} but is actually of length {}",
-                len,
-                t.len()
-            ))?;
-            t = t[0..len].to_vec();
-        }
+                len, actual
+            )
+        })?;
         while t.len() < len {
             t.push(g.arbitrary(|| {
                 format!(
@@ -78,23 +182,26 @@ where
 }
 
 /// Combines a LenFact with a VecFact to ensure that the vector is of a given length
+#[cfg(feature = "arbitrary")]
 pub fn vec_of_length<'a, T>(len: usize, inner_fact: impl Fact<'a, T>) -> impl Fact<'a, Vec<T>>
 where
-    T: Target<'a> + 'a,
+    T: Target<'a> + 'a + for<'u> arbitrary::Arbitrary<'u>,
 {
     and(vec_len(len), vec(inner_fact))
 }
 
 #[cfg(test)]
+#[cfg(feature = "utils")]
 mod tests {
     use std::sync::{
-        atomic::{AtomicU8, Ordering},
+        atomic::{AtomicU32, AtomicU8, Ordering},
         Arc,
     };
 
     use crate::facts::*;
     use arbitrary::*;
 
+    #[allow(dead_code)]
     #[derive(Debug, Clone, PartialEq, Arbitrary)]
     struct S {
         x: u32,
@@ -139,6 +246,135 @@ mod tests {
         assert_eq!(count, vec![0, 1, 2, 3, 4]);
     }
 
+    #[test]
+    fn test_vec_reports_every_failure() {
+        let failures = vec(eq(1))
+            .check(&vec![0, 1, 2, 1, 3])
+            .result()
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(
+            failures
+                .iter()
+                .map(|f| f.split(':').next().unwrap())
+                .collect::<Vec<_>>(),
+            vec!["[0]", "[2]", "[4]"]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn test_par_vec_matches_vec() {
+        let stateless = || {
+            facts![
+                lens1("0", |t: &mut (u8, u8)| &mut t.0, eq(1)),
+                lens1("1", |t: &mut (u8, u8)| &mut t.1, in_range("small", 0..5)),
+            ]
+        };
+        assert!(stateless().is_stateless());
+        let items: Vec<(u8, u8)> = (0..200u8).map(|i| (i % 3, i % 7)).collect();
+        assert_eq!(
+            par_vec(stateless()).check(&items),
+            vec(stateless()).check(&items)
+        );
+        // Labels are added the same way in parallel
+        let labeled = || facts![eq((1, 1)).labeled("ones"), stateless()];
+        let check = par_vec(labeled()).labeled("items").check(&items);
+        assert_eq!(check, vec(labeled()).labeled("items").check(&items));
+        let failures = check.failures().unwrap();
+        assert!(failures.iter().any(|f| f.contains("items: ")));
+        assert!(failures.iter().any(|f| f.contains("ones: ")));
+        assert!(!failures
+            .iter()
+            .any(|f| f.contains("items: ") && f.contains("ones: ")));
+
+        // The path on the way goes before each failure too, with or without a label
+        let pair = (items.clone(), 0u8);
+        fn all(t: &mut (Vec<(u8, u8)>, u8)) -> &mut Vec<(u8, u8)> {
+            &mut t.0
+        }
+        let stateful = || lambda("stateful", (), |_, (), t| Ok(t));
+        assert_eq!(
+            lens1("all", all, par_vec(labeled()))
+                .labeled("items")
+                .check(&pair),
+            lens1("all", all, vec(labeled()))
+                .labeled("items")
+                .check(&pair)
+        );
+        let check = facts![lens1("all", all, par_vec(stateless())), stateful()].check(&pair);
+        assert_eq!(
+            check,
+            facts![lens1("all", all, vec(stateless())), stateful()].check(&pair)
+        );
+        assert!(check.failures().unwrap()[0].starts_with("all: [0]: 0: "));
+
+        let stateful = || facts![eq(1), consecutive_int_(0u8)];
+        assert!(!stateful().is_stateless());
+        let items = vec![0, 1, 1, 3];
+        assert_eq!(
+            par_vec(stateful()).check(&items),
+            vec(stateful()).check(&items)
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    static CLONES: AtomicU32 = AtomicU32::new(0);
+
+    /// Counts how many times it's cloned, on any thread
+    #[cfg(feature = "rayon")]
+    #[derive(Debug, PartialEq)]
+    struct Counted(u32);
+
+    #[cfg(feature = "rayon")]
+    impl Clone for Counted {
+        fn clone(&self) -> Self {
+            CLONES.fetch_add(1, Ordering::SeqCst);
+            Self(self.0)
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn test_par_vec_check_mut_doesnt_clone() {
+        let f = par_vec(lens1("0", |t: &mut (u8, Counted)| &mut t.0, eq(1)));
+        let mut items: Vec<(u8, Counted)> = (0..100).map(|i| (1, Counted(i))).collect();
+        items[7].0 = 2;
+
+        CLONES.store(0, Ordering::SeqCst);
+        assert_eq!(
+            f.check_mut(&mut items).failures().unwrap(),
+            &["[7]: 0: expected 2 == 1".to_string()]
+        );
+        assert_eq!(CLONES.load(Ordering::SeqCst), 0);
+    }
+
+    /// Like `consecutive_int`, but the count is shared between clones
+    #[derive(Clone, Debug)]
+    struct SharedCounter(Arc<AtomicU32>);
+
+    impl<'a> Fact<'a, u32> for SharedCounter {
+        fn labeled(self, _: impl ToString) -> Self {
+            self
+        }
+
+        fn label(&self) -> String {
+            "SharedCounter".to_string()
+        }
+
+        fn mutate(&mut self, g: &mut Generator<'_>, mut t: u32) -> Mutation<u32> {
+            let c = self.0.load(Ordering::SeqCst);
+            g.set(&mut t, &c, || "not counting")?;
+            self.0.store(c + 1, Ordering::SeqCst);
+            Ok(t)
+        }
+
+        fn save_state(&self) -> Snapshot<'a, Self> {
+            let c = self.0.load(Ordering::SeqCst);
+            Snapshot::new(move |f: &mut Self| f.0.store(c, Ordering::SeqCst))
+        }
+    }
+
     /// Assert that even when satisfy() requires a fact to be run
     /// multiple times due to contradictory facts, if the constraint
     /// can be eventually satisfied, the facts still advance only
@@ -166,13 +402,36 @@ mod tests {
             assert_eq!(items, vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
         }
 
+        // Assert that a fact whose state is behind an Arc rolls back too,
+        // since it says how to snapshot its state
+        {
+            let counter = SharedCounter(Arc::new(AtomicU32::new(0)));
+            let f = vec_of_length(10, facts!(counter, piecewise()));
+            let items = f.build(&mut g);
+            assert_eq!(items, vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        }
+
+        // ...including when it's inside combinators which snapshot the facts they're made of
+        {
+            let counter = || SharedCounter(Arc::new(AtomicU32::new(0)));
+            let f = vec_of_length(10, facts!(guard(always(), counter()), piecewise()));
+            assert_eq!(f.build(&mut g), vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+            let f = vec_of_length(
+                10,
+                facts!(when(always(), counter(), never("unreachable")), piecewise()),
+            );
+            assert_eq!(f.build(&mut g), vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        }
+
         // Assert that piecewise() messes everything up during the
-        // first 3 mutations, and cooperates afterwards
+        // first 3 mutations, and cooperates afterwards.
+        // (A check keeps going after the first failure, so it runs a fresh copy
+        // of the facts, leaving the count of the ones being mutated alone.)
         {
             let mut f = facts!(eq(0), piecewise());
             for _ in 0..3 {
                 let val = f.mutate(&mut g, 0).unwrap();
-                assert!(f.clone().check(&val).is_err());
+                assert!(facts!(eq(0), piecewise()).check(&val).is_err());
             }
             let val = f.mutate(&mut g, 0).unwrap();
             f.check(&val).unwrap();
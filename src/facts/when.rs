@@ -0,0 +1,155 @@
+use super::*;
+
+/// The number of times `when` will switch branches during a single mutation,
+/// when applying one branch flips the condition.
+const WHEN_BRANCH_LIMIT: usize = 10;
+
+/// A Fact which applies `then` to values which meet the `condition`, and
+/// `otherwise` to values which don't.
+///
+/// Unlike `Either`, the branch is chosen from the data itself, by checking the
+/// condition against the current value. The condition is never mutated.
+///
+/// If mutating with one branch changes the value so that the condition flips,
+/// the other branch is applied to the new value, until the condition settles.
+///
+/// ```
+/// # #[cfg(feature = "utils")] {
+/// use contrafact::{*, facts::*};
+///
+/// let fact = when(
+///     brute("small", |x: &u32| *x < 100),
+///     brute("even", |x: &u32| x % 2 == 0),
+///     brute("odd", |x: &u32| x % 2 == 1),
+/// );
+///
+/// fact.clone().check(&4).unwrap();
+/// fact.clone().check(&101).unwrap();
+/// assert!(fact.clone().check(&3).is_err());
+/// assert!(fact.clone().check(&100).is_err());
+/// # }
+/// ```
+pub fn when<'a, T, C, A, B>(condition: C, then: A, otherwise: B) -> impl Fact<'a, T>
+where
+    T: Target<'a>,
+    C: Fact<'a, T>,
+    A: Fact<'a, T>,
+    B: Fact<'a, T>,
+{
+    lambda(
+        "when",
+        (condition, then, otherwise),
+        |g, (condition, then, otherwise), mut t| {
+            let mut holds = g.probe(condition, &t);
+            for _ in 0..WHEN_BRANCH_LIMIT {
+                t = if holds {
+                    then.mutate(g, t)?
+                } else {
+                    otherwise.mutate(g, t)?
+                };
+                // A check leaves the value alone, so the condition can't have flipped
+                if g.is_checking() {
+                    return Ok(t);
+                }
+                let flipped = g.probe(condition, &t) != holds;
+                if !flipped {
+                    return Ok(t);
+                }
+                holds = !holds;
+            }
+            // The branches keep flipping the condition. Let `satisfy` try again.
+            Ok(t)
+        },
+    )
+    .describe_with(|label, (condition, then, otherwise)| {
+        Description::new("when", label).with_children([
+            condition.describe(),
+            then.describe(),
+            otherwise.describe(),
+        ])
+    })
+    .label_with(|label, (condition, then, otherwise)| {
+        label_children(label, &[condition.label(), then.label(), otherwise.label()])
+    })
+    .snapshot_with(|(condition, then, otherwise)| {
+        let snapshots = (
+            condition.save_state(),
+            then.save_state(),
+            otherwise.save_state(),
+        );
+        Snapshot::new(move |(c, t, o): &mut (C, A, B)| {
+            c.restore_state(&snapshots.0);
+            t.restore_state(&snapshots.1);
+            o.restore_state(&snapshots.2);
+        })
+    })
+    .stateless_with(|(condition, then, otherwise)| {
+        condition.is_stateless() && then.is_stateless() && otherwise.is_stateless()
+    })
+}
+
+/// A Fact which applies `consequence` only to values which meet the `condition`.
+///
+/// This is `when(condition, consequence, always())`.
+pub fn implies<'a, T>(
+    condition: impl Fact<'a, T>,
+    consequence: impl Fact<'a, T>,
+) -> impl Fact<'a, T>
+where
+    T: Target<'a>,
+{
+    when(condition, consequence, always())
+}
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_when() {
+        observability::test_run().ok();
+        let mut g = utils::random_generator();
+
+        let fact = || {
+            vec(when(
+                brute("small", |x: &u8| *x < 100),
+                in_range("0..10", 0..10),
+                in_range("200..", 200..),
+            ))
+        };
+        let xs = fact().build(&mut g);
+        fact().check(&xs).unwrap();
+        assert!(xs.iter().all(|x| *x < 10 || *x >= 200));
+    }
+
+    #[test]
+    fn test_when_branch_flips_condition() {
+        observability::test_run().ok();
+        let mut g = utils::random_generator();
+
+        // Setting a small value to 20 makes it not small, so the other branch applies
+        let fact = when(brute("small", |x: &u8| *x < 10), eq(20), eq(30));
+        assert_eq!(fact.clone().satisfy(&mut g, 5).unwrap(), 30);
+        assert_eq!(fact.clone().satisfy(&mut g, 50).unwrap(), 30);
+        assert!(fact.clone().check(&20).is_err());
+        fact.check(&30).unwrap();
+    }
+
+    #[test]
+    fn test_implies() {
+        observability::test_run().ok();
+        let mut g = utils::random_generator();
+
+        let fact = || {
+            vec(implies(
+                brute("even", |x: &u8| x.is_multiple_of(2)),
+                in_range("<= 10", 0..=10),
+            ))
+        };
+        let xs = fact().build(&mut g);
+        fact().check(&xs).unwrap();
+        assert!(xs.iter().all(|x| !x.is_multiple_of(2) || *x <= 10));
+        assert!(fact().check(&vec![1, 2, 3, 12]).is_err());
+    }
+}
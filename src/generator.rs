@@ -12,44 +12,448 @@
 //! was not met. When running Fact::mutate, no error is thrown, and new data is produced instead.
 //! All Facts must be written with this dual use in mind.
 
-use arbitrary::{Arbitrary, Unstructured};
-
 use crate::error::*;
-use arbitrary::unstructured::Int;
+use crate::source::{Fallback, Source};
+use crate::{Change, Check, Coverage, Fact, Target};
 use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+/// When generating arbitrary data from an endless Source, draw this many bytes
+/// at a time. Any bytes not used by one value are kept for the next.
+#[cfg(feature = "arbitrary")]
+const ARBITRARY_CHUNK: usize = 1024;
+
+/// How often [`Generator::int_biased_towards_edges`] chooses an edge, in percent
+const EDGE_PERCENT: u8 = 50;
+
+/// One segment of a Generator's path
+#[derive(Clone, Debug)]
+enum Segment {
+    /// The label of a fact which focuses on part of the data, like a lens
+    Label(Arc<str>),
+    /// The index of an item in a sequence
+    Index(usize),
+    /// A label given with [`Fact::labeled`], which isn't part of the path to the
+    /// data, but goes before the failures of the fact it was given to
+    Labeled(Arc<str>),
+}
+
+impl std::fmt::Display for Segment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Segment::Label(label) | Segment::Labeled(label) => f.write_str(label),
+            Segment::Index(i) => write!(f, "[{}]", i),
+        }
+    }
+}
+
+/// The Source of a Generator which only checks, and so never draws any bytes.
+/// Unlike an empty slice, it can be boxed without allocating.
+struct NoSource;
+
+impl Source for NoSource {
+    fn fill(&mut self, _buf: &mut [u8]) -> usize {
+        0
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        Some(0)
+    }
+}
 
 /// Generators are used to generate new values and error messages.
 ///
 /// For mutation logic which actually generates new data, error messages are produced instead of data during a Check.
 /// In some cases, `Generator::fail` must be used when attempting to mutate data using existing values not generated by Generator.
+///
+/// A Generator draws all of its raw bytes from a [`Source`], so that it can be driven by
+/// a fuzzer, a random number generator, or a [`ReplayLog`](crate::ReplayLog) of a previous run.
+/// The Generator's lifetime is only the lifetime of its Source. Facts are not tied to it,
+/// so the same Fact can be used with many Generators over different buffers.
 #[must_use = "Be sure to use Generator::fail even if you're not generating new values, to provide an error message when running check()"]
-#[derive(derive_more::Deref, derive_more::DerefMut)]
 pub struct Generator<'a> {
-    #[deref]
-    #[deref_mut]
-    arb: Unstructured<'a>,
+    source: Box<dyn 'a + Source>,
+
+    /// Bytes which were drawn from the source. Those from `cursor` on are not yet used.
+    buffer: Vec<u8>,
+
+    /// The position of the next unused byte in the buffer
+    cursor: usize,
+
+    /// The number of bytes used so far
+    consumed: usize,
+
+    /// Failures recorded during a check by [`Generator::accumulate`]
+    failures: Vec<Failure>,
+
+    /// The path from the outermost fact to the one currently running,
+    /// along with the labels of the labeled facts on the way
+    path: Vec<Segment>,
+
+    /// Changes recorded during mutation, when recording for a [`SatisfyReport`]
+    changes: Option<Vec<Change>>,
+
+    /// Which branches of `prism` and `or` have been reached, if being tracked
+    coverage: Option<Coverage>,
 
     check: bool,
 }
 
-impl<'a> From<Unstructured<'a>> for Generator<'a> {
-    fn from(arb: Unstructured<'a>) -> Self {
-        assert!(!arb.is_empty());
-        Self { arb, check: false }
+#[cfg(feature = "arbitrary")]
+impl<'a> From<arbitrary::Unstructured<'a>> for Generator<'a> {
+    fn from(arb: arbitrary::Unstructured<'a>) -> Self {
+        Self::new(arb)
     }
 }
 
 impl<'a> From<&'a [u8]> for Generator<'a> {
     fn from(bytes: &'a [u8]) -> Self {
-        arbitrary::Unstructured::new(bytes).into()
+        Self::new(bytes)
     }
 }
 
 impl<'a> Generator<'a> {
+    /// Create a Generator which draws its bytes from the given Source
+    pub fn new(source: impl 'a + Source) -> Self {
+        Self {
+            source: Box::new(source),
+            buffer: vec![],
+            cursor: 0,
+            consumed: 0,
+            failures: vec![],
+            path: vec![],
+            changes: None,
+            coverage: None,
+            check: false,
+        }
+    }
+
+    /// Once the Source runs dry, keep going with bytes derived by hashing
+    /// everything drawn from it so far, rather than failing with
+    /// [`MutationError::Exhausted`].
+    ///
+    /// The extra bytes are deterministic, so a run over the same Source can
+    /// still be reproduced exactly. This is useful for long `build` runs which
+    /// would otherwise fail halfway through a small fuzzer input.
+    ///
+    /// ```
+    /// use contrafact::*;
+    ///
+    /// let mut g = Generator::from(&[1u8, 2][..]).with_fallback();
+    /// let bytes = g.bytes(100, || "").unwrap();
+    /// assert_eq!(bytes.len(), 100);
+    /// assert_eq!(g.consumed(), 100);
+    ///
+    /// let mut g = Generator::from(&[1u8, 2][..]).with_fallback();
+    /// assert_eq!(g.bytes(100, || "").unwrap(), bytes);
+    /// ```
+    pub fn with_fallback(mut self) -> Self {
+        let source = std::mem::replace(&mut self.source, Box::new(&[][..]));
+        self.source = Box::new(Fallback::new(source));
+        self
+    }
+
+    /// Track which branches of each `prism` and `or` are reached by the values
+    /// built with this Generator, and steer later builds towards the branches
+    /// which haven't been reached yet. See [`Coverage`].
+    pub fn with_coverage(mut self, coverage: Coverage) -> Self {
+        self.coverage = Some(coverage);
+        self
+    }
+
+    /// The Coverage being tracked, if any
+    pub fn coverage(&self) -> Option<&Coverage> {
+        self.coverage.as_ref()
+    }
+
+    /// The key for a point of coverage at the current path.
+    /// Vec indices are collapsed, so that all items share the same points.
+    fn coverage_key(&self, name: &str) -> String {
+        self.path
+            .iter()
+            .filter_map(|s| match s {
+                Segment::Label(label) => Some(label.as_ref()),
+                Segment::Index(_) => Some("[*]"),
+                Segment::Labeled(_) => None,
+            })
+            .chain(std::iter::once(name))
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Count a hit on one branch of a `prism` or `or`, if coverage is being tracked.
+    /// Only checks count, so that each built value is counted once.
+    pub(crate) fn cover(
+        &self,
+        kind: &str,
+        name: impl FnOnce() -> String,
+        branches: &[&'static str],
+        branch: usize,
+    ) {
+        if let (true, Some(coverage)) = (self.check, &self.coverage) {
+            coverage.hit(self.coverage_key(&name()), kind, branches, branch);
+        }
+    }
+
+    /// Check a value against a fact on the side, e.g. to decide which branch to take,
+    /// without recording any failures in this check.
+    ///
+    /// If coverage is being tracked by this check, the side check shares it, along
+    /// with the current path, so that a `prism` or `or` inside the fact is still counted.
+    pub(crate) fn probe<'t, T: Target<'t>>(&self, fact: &impl Fact<'t, T>, t: &T) -> bool {
+        match (self.check, &self.coverage) {
+            (true, Some(coverage)) => {
+                let mut checker = Generator::checker().with_coverage(coverage.clone());
+                checker.path = self.path.clone();
+                let result = fact.clone().mutate(&mut checker, t.clone());
+                checker.into_check(result).is_ok()
+            }
+            _ => fact.clone().check(t).is_ok(),
+        }
+    }
+
+    /// The branch of a `prism` or `or` which has been reached least often,
+    /// if coverage is being tracked and some branch is behind the others
+    pub(crate) fn least_covered(&self, name: impl FnOnce() -> String) -> Option<usize> {
+        self.coverage
+            .as_ref()?
+            .least_hit(&self.coverage_key(&name()))
+    }
+
     pub(crate) fn checker() -> Self {
         Self {
-            arb: arbitrary::Unstructured::new(&[]),
             check: true,
+            ..Self::new(NoSource)
+        }
+    }
+
+    /// The number of bytes of entropy left, or `None` if there is no limit.
+    pub fn remaining(&self) -> Option<usize> {
+        self.source.remaining().map(|n| n + self.buffered())
+    }
+
+    /// The number of bytes drawn from the source but not yet used
+    fn buffered(&self) -> usize {
+        self.buffer.len() - self.cursor
+    }
+
+    /// The number of bytes of entropy used so far.
+    pub fn consumed(&self) -> usize {
+        self.consumed
+    }
+
+    fn is_empty(&self) -> bool {
+        self.remaining() == Some(0)
+    }
+
+    /// Take the next byte, either from the buffer or from the source
+    fn next_byte(&mut self) -> Option<u8> {
+        let byte = if self.buffered() == 0 {
+            let mut byte = [0];
+            (self.source.fill(&mut byte) == 1).then_some(byte[0])
+        } else {
+            self.cursor += 1;
+            Some(self.buffer[self.cursor - 1])
+        };
+        self.consumed += byte.is_some() as usize;
+        byte
+    }
+
+    /// Whether this Generator is running a Check rather than a mutation
+    pub fn is_checking(&self) -> bool {
+        self.check
+    }
+
+    /// "check" or "mutate", for tracing
+    pub(crate) fn mode(&self) -> &'static str {
+        if self.check {
+            "check"
+        } else {
+            "mutate"
+        }
+    }
+
+    /// The path to the part of the data currently being mutated or checked,
+    /// as a list of segments such as lens labels and vec indices separated by `/`.
+    pub fn path(&self) -> String {
+        self.path
+            .iter()
+            .filter(|s| !matches!(s, Segment::Labeled(_)))
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Run a function within a fact which was given a label with [`Fact::labeled`].
+    /// Each failure made along the way has the label before the rest of the path
+    /// within the fact, unless there's another labeled fact closer to it.
+    pub(crate) fn with_label<R>(&mut self, label: &Arc<str>, f: impl FnOnce(&mut Self) -> R) -> R {
+        self.in_segment(Segment::Labeled(label.clone()), f)
+    }
+
+    /// Whether a fact on the way here was given a label with [`Fact::labeled`]
+    #[cfg(feature = "rayon")]
+    pub(crate) fn is_labeled(&self) -> bool {
+        self.path.iter().any(|s| matches!(s, Segment::Labeled(_)))
+    }
+
+    /// Make checkers which start from the current path, e.g. to check items on other threads
+    #[cfg(feature = "rayon")]
+    pub(crate) fn checkers(&self) -> impl Fn() -> Generator<'static> + Sync {
+        let path = self.path.clone();
+        move || Generator {
+            path: path.clone(),
+            ..Generator::checker()
+        }
+    }
+
+    /// What goes before each failure made here: each segment of the path, and
+    /// the nearest label, in the order they were reached, like `[1]: one: S::x: `
+    pub(crate) fn prefix(&self) -> String {
+        let nearest = self
+            .path
+            .iter()
+            .rposition(|s| matches!(s, Segment::Labeled(_)));
+        self.path
+            .iter()
+            .enumerate()
+            .filter(|(i, s)| !matches!(s, Segment::Labeled(_)) || Some(*i) == nearest)
+            .map(|(_, s)| format!("{}: ", s))
+            .collect()
+    }
+
+    /// A failure of a check, with the path and the nearest label before it
+    fn failure(&self, err: impl ToString) -> MutationError {
+        MutationError::Check(format!("{}{}", self.prefix(), err.to_string()))
+    }
+
+    /// Run a function with a segment added to the [`Generator::path`].
+    /// Facts which focus on some part of the data, like `lens` and `vec`, use this.
+    /// The segment also goes before each failure made along the way.
+    pub fn in_path<R>(&mut self, segment: impl ToString, f: impl FnOnce(&mut Self) -> R) -> R {
+        self.in_segment(Segment::Label(segment.to_string().into()), f)
+    }
+
+    /// Like [`Generator::in_path`] with a label which is shared rather than
+    /// copied, so that it costs nothing on every check
+    pub(crate) fn in_label<R>(&mut self, label: &Arc<str>, f: impl FnOnce(&mut Self) -> R) -> R {
+        self.in_segment(Segment::Label(label.clone()), f)
+    }
+
+    /// Like [`Generator::in_path`] with the index of an item in a sequence
+    pub(crate) fn in_index<R>(&mut self, index: usize, f: impl FnOnce(&mut Self) -> R) -> R {
+        self.in_segment(Segment::Index(index), f)
+    }
+
+    fn in_segment<R>(&mut self, segment: Segment, f: impl FnOnce(&mut Self) -> R) -> R {
+        self.path.push(segment);
+        let r = f(self);
+        self.path.pop();
+        r
+    }
+
+    /// Whether changes are being recorded for a [`SatisfyReport`]
+    pub(crate) fn is_recording(&self) -> bool {
+        self.changes.is_some()
+    }
+
+    /// The number of changes recorded so far
+    pub(crate) fn changes_recorded(&self) -> usize {
+        self.changes.as_ref().map_or(0, Vec::len)
+    }
+
+    /// Record a change, if recording
+    pub(crate) fn record_change(&mut self, change: Change) {
+        if let Some(changes) = self.changes.as_mut() {
+            changes.push(change);
+        }
+    }
+
+    /// Replace the changes being recorded (`None` to stop recording),
+    /// returning the changes recorded so far
+    pub(crate) fn swap_changes(&mut self, changes: Option<Vec<Change>>) -> Option<Vec<Change>> {
+        std::mem::replace(&mut self.changes, changes)
+    }
+
+    /// Run a mutation which is only one part of a larger mutation.
+    ///
+    /// When running a Check, a failure is recorded instead of being returned, and the
+    /// original value is passed along, so that the rest of the check can carry on and
+    /// report every failure rather than just the first. During mutation, this just
+    /// runs the mutation.
+    ///
+    /// Combinators which apply several facts, like `and` and `vec`, use this for
+    /// each of their parts.
+    pub fn accumulate<T: Clone>(
+        &mut self,
+        t: T,
+        f: impl FnOnce(&mut Self, T) -> Mutation<T>,
+    ) -> Mutation<T> {
+        if !self.check {
+            return f(self, t);
+        }
+        match f(self, t.clone()) {
+            Err(MutationError::Check(failure)) => {
+                self.failures.push(failure);
+                Ok(t)
+            }
+            result => result,
+        }
+    }
+
+    /// Run a mutation which must succeed for the rest of a check to make sense.
+    ///
+    /// When running a Check, any failure of this mutation (including failures
+    /// recorded by [`Generator::accumulate`] along the way) aborts the whole check
+    /// with [`MutationError::Abort`]. During mutation, this just runs the mutation.
+    ///
+    /// To skip only the facts which depend on the precondition, rather than the
+    /// whole check, use [`facts::guard`](crate::facts::guard).
+    pub fn precondition<T>(
+        &mut self,
+        t: T,
+        f: impl FnOnce(&mut Self, T) -> Mutation<T>,
+    ) -> Mutation<T> {
+        if !self.check {
+            return f(self, t);
+        }
+        let before = self.failures.len();
+        let failure = match f(self, t) {
+            Ok(t) if self.failures.len() == before => return Ok(t),
+            Ok(_) => self.failures.pop().expect("a failure was recorded"),
+            Err(MutationError::Check(failure)) => failure,
+            Err(err) => return Err(err),
+        };
+        Err(MutationError::Abort(failure))
+    }
+
+    /// Fold a check which was run separately, e.g. on another thread, into this one,
+    /// as if it had been run with [`Generator::accumulate`].
+    #[cfg(feature = "rayon")]
+    pub(crate) fn absorb(&mut self, check: Check) -> Mutation<()> {
+        match check {
+            Check::Failures(failures) => {
+                self.failures.extend(failures);
+                Ok(())
+            }
+            Check::Abort(mut failures) => {
+                let last = failures.pop().unwrap_or_default();
+                self.failures.extend(failures);
+                Err(MutationError::Abort(last))
+            }
+            Check::Error(err) => Err(MutationError::User(err)),
+        }
+    }
+
+    /// Finish a check, combining the failures recorded along the way with the
+    /// final result of the mutation.
+    pub(crate) fn into_check<T>(self, result: Mutation<T>) -> Check {
+        match Check::from_mutation(result) {
+            Check::Failures(last) => {
+                Check::Failures(self.failures.into_iter().chain(last).collect())
+            }
+            Check::Abort(last) => Check::Abort(self.failures.into_iter().chain(last).collect()),
+            error => error,
         }
     }
 
@@ -57,8 +461,14 @@ impl<'a> Generator<'a> {
     /// This should be used in cases where a mutation occurs using some known value, rather than
     /// generating a value from the Generator itself.
     pub fn fail(&self, err: impl ToString) -> Mutation<()> {
+        self.fail_with(|| err)
+    }
+
+    /// Like [`Generator::fail`], but the error is only built if there is a check
+    /// to fail, so that mutations don't pay for formatting it.
+    pub fn fail_with<S: ToString>(&self, err: impl FnOnce() -> S) -> Mutation<()> {
         if self.check {
-            Err(MutationError::Check(err.to_string()))
+            Err(self.failure(err()))
         } else {
             Ok(())
         }
@@ -74,7 +484,7 @@ impl<'a> Generator<'a> {
     ) -> Mutation<()> {
         if source != target {
             if self.check {
-                return Err(MutationError::Check(err().to_string()));
+                return Err(self.failure(err()));
             } else {
                 *source = target.clone();
             }
@@ -82,34 +492,247 @@ impl<'a> Generator<'a> {
         Ok(())
     }
 
-    /// Generate arbitrary data in mutation mode, or produce an error in check mode
-    pub fn arbitrary<T: Arbitrary<'a>, S: ToString>(
+    /// When running a Check, fail immediately with this error if applying the function
+    /// would change the value. During mutation, apply the function.
+    ///
+    /// This is like [`Generator::set`], but for updating only part of a value.
+    pub fn set_with<T: PartialEq + Clone, S: ToString>(
+        &self,
+        source: &mut T,
+        f: impl FnOnce(&mut T),
+        err: impl FnOnce() -> S,
+    ) -> Mutation<()> {
+        if self.check {
+            let mut target = source.clone();
+            f(&mut target);
+            self.set(source, &target, err)
+        } else {
+            f(source);
+            Ok(())
+        }
+    }
+
+    /// If the condition doesn't hold, then when running a Check, fail immediately
+    /// with this error, and during mutation, run the repair function.
+    ///
+    /// ```
+    /// # #[cfg(feature = "utils")] {
+    /// use contrafact::*;
+    ///
+    /// let fact = lambda_unit("even", |g, mut x: u8| {
+    ///     g.ensure(x % 2 == 0, || "x is odd", |_| {
+    ///         x = x.wrapping_add(1);
+    ///         Ok(())
+    ///     })?;
+    ///     Ok(x)
+    /// });
+    /// assert_eq!(fact.clone().check(&3), Check::fail("x is odd"));
+    /// assert_eq!(fact.build_with(&mut utils::random_generator(), |_| Ok(3)), 4);
+    /// # }
+    /// ```
+    pub fn ensure<S: ToString>(
         &mut self,
+        cond: bool,
         err: impl FnOnce() -> S,
-    ) -> Mutation<T> {
+        repair: impl FnOnce(&mut Self) -> Mutation<()>,
+    ) -> Mutation<()> {
+        if cond {
+            Ok(())
+        } else if self.check {
+            Err(self.failure(err()))
+        } else {
+            repair(self)
+        }
+    }
+
+    /// When running a Check, fail immediately with this error.
+    /// During mutation, push the item onto the Vec.
+    pub fn push<T, S: ToString>(
+        &self,
+        vec: &mut Vec<T>,
+        item: T,
+        err: impl FnOnce() -> S,
+    ) -> Mutation<()> {
+        self.fail_with(err)?;
+        vec.push(item);
+        Ok(())
+    }
+
+    /// When running a Check, fail immediately with this error if the Vec is
+    /// longer than `len`. During mutation, truncate it to `len`.
+    pub fn truncate<T, S: ToString>(
+        &self,
+        vec: &mut Vec<T>,
+        len: usize,
+        err: impl FnOnce() -> S,
+    ) -> Mutation<()> {
+        if vec.len() > len {
+            self.fail_with(err)?;
+            vec.truncate(len);
+        }
+        Ok(())
+    }
+
+    /// Generate arbitrary data in mutation mode, or produce an error in check mode
+    #[cfg(feature = "arbitrary")]
+    pub fn arbitrary<T, S: ToString>(&mut self, err: impl FnOnce() -> S) -> Mutation<T>
+    where
+        T: for<'u> arbitrary::Arbitrary<'u>,
+    {
         self.with(err, |u| u.arbitrary())
     }
 
+    /// Draw this many raw bytes in mutation mode, or produce an error in check mode.
+    ///
+    /// This is the building block for generating types which don't implement `Arbitrary`.
+    pub fn bytes<S: ToString>(&mut self, len: usize, err: impl FnOnce() -> S) -> Mutation<Vec<u8>> {
+        if self.check {
+            return Err(self.failure(err()));
+        }
+        let mut bytes = Vec::with_capacity(len);
+        while bytes.len() < len {
+            match self.next_byte() {
+                Some(b) => bytes.push(b),
+                None => return Err(MutationError::Exhausted),
+            }
+        }
+        Ok(bytes)
+    }
+
     /// Choose between specified items in mutation mode, or produce an error in check mode.
-    pub fn choose<T: Arbitrary<'a>, S: ToString>(
+    pub fn choose<'c, T, S: ToString>(
+        &mut self,
+        choices: &'c [T],
+        err: impl FnOnce() -> S,
+    ) -> Mutation<&'c T> {
+        let i = self.choose_index(choices.len(), err)?;
+        Ok(&choices[i])
+    }
+
+    /// Choose between specified items in mutation mode, with the probability of each
+    /// being proportional to its weight, or produce an error in check mode.
+    ///
+    /// ```
+    /// # #[cfg(feature = "utils")] {
+    /// use contrafact::*;
+    ///
+    /// let mut g = utils::random_generator();
+    /// let sizes = [(10, 9), (10_000, 1)];
+    /// let small = (0..1000)
+    ///     .filter(|_| *g.choose_weighted(&sizes, || "").unwrap() == 10)
+    ///     .count();
+    /// assert!(small > 800 && small < 980);
+    /// # }
+    /// ```
+    pub fn choose_weighted<'c, T, S: ToString>(
+        &mut self,
+        choices: &'c [(T, u32)],
+        err: impl FnOnce() -> S,
+    ) -> Mutation<&'c T> {
+        let total: u64 = choices.iter().map(|(_, w)| *w as u64).sum();
+        if total == 0 {
+            return Err(MutationError::User("Empty choices".to_string()));
+        }
+        let mut r = self.int_in_range(0..=total - 1, err)?;
+        for (item, weight) in choices {
+            if r < *weight as u64 {
+                return Ok(item);
+            }
+            r -= *weight as u64;
+        }
+        unreachable!("r is less than the total weight")
+    }
+
+    /// Choose an integer in the given range in mutation mode, or produce an error
+    /// in check mode, like [`Generator::int_in_range`], except that the edges of
+    /// the range and zero are chosen much more often, to help find boundary bugs.
+    pub fn int_biased_towards_edges<T, S>(
+        &mut self,
+        range: RangeInclusive<T>,
+        err: impl FnOnce() -> S,
+    ) -> Mutation<T>
+    where
+        T: Int,
+        S: ToString,
+    {
+        let zero = T::from_unsigned(0);
+        let mut edges = vec![*range.start(), *range.end()];
+        if range.contains(&zero) {
+            edges.push(zero);
+        }
+        self.int_with_edges(range, edges, EDGE_PERCENT, err)
+    }
+
+    /// Choose one of the edges with the given probability (in percent),
+    /// or else any integer in the range
+    pub(crate) fn int_with_edges<T, S>(
+        &mut self,
+        range: RangeInclusive<T>,
+        edges: Vec<T>,
+        percent: u8,
+        err: impl FnOnce() -> S,
+    ) -> Mutation<T>
+    where
+        T: Int,
+        S: ToString,
+    {
+        if range.start() >= range.end() {
+            return self.int_in_range(range, err);
+        }
+        if self.check {
+            return Err(self.failure(err()));
+        }
+        let mut unique = Vec::with_capacity(edges.len());
+        for e in edges {
+            if range.contains(&e) && !unique.contains(&e) {
+                unique.push(e);
+            }
+        }
+        let edges = unique;
+        if !edges.is_empty() && self.int_in_range(0..=99u8, || "")? < percent {
+            self.choose_owned(edges, err)
+        } else {
+            self.int_in_range(range, err)
+        }
+    }
+
+    /// Choose an index into a collection of this many choices
+    fn choose_index<S: ToString>(
         &mut self,
-        choices: &'a [T],
+        len: usize,
         err: impl FnOnce() -> S,
-    ) -> Mutation<&T> {
-        if choices.is_empty() {
-            return Err(MutationError::User("Empty choices".to_string())).into();
+    ) -> Mutation<usize> {
+        if len == 0 {
+            return Err(MutationError::User("Empty choices".to_string()));
         }
-        if choices.len() == 1 {
-            return Ok(&choices[0]).into();
+        if len == 1 {
+            return Ok(0);
         }
-        if !self.check && self.arb.is_empty() {
-            return Err(MutationError::User("Ran out of entropy".to_string())).into();
+        if !self.check && self.is_empty() {
+            return Err(MutationError::Exhausted);
         }
-        self.with(err, |u| u.choose(choices))
+        self.int_in_range(0..=len - 1, err)
     }
 
-    /// Exposes `int_in_range` from underlying `Unstructured` in mutation mode,
+    /// Choose between specified items in mutation mode, or produce an error in check mode.
+    ///
+    /// Unlike [`Generator::choose`], the choices don't need to be borrowed from anywhere,
+    /// and the chosen item is returned by value.
+    pub fn choose_owned<T, S: ToString>(
+        &mut self,
+        choices: impl IntoIterator<Item = T>,
+        err: impl FnOnce() -> S,
+    ) -> Mutation<T> {
+        let mut choices: Vec<T> = choices.into_iter().collect();
+        let i = self.choose_index(choices.len(), err)?;
+        Ok(choices.swap_remove(i))
+    }
+
+    /// Choose an integer in the given range in mutation mode,
     /// or produce an error in check mode.
+    ///
+    /// Only as many bytes as are needed to cover the range are consumed,
+    /// the same way as `arbitrary::Unstructured::int_in_range`.
     /// Note that even though arbitrary says NOT to use this for calculating the
     /// size of a collection to build, that's exactly what I will be doing with
     /// this, because I'm not sure exactly what the contrafact story is for
@@ -120,35 +743,158 @@ impl<'a> Generator<'a> {
         err: impl FnOnce() -> S,
     ) -> Mutation<T>
     where
-        T: Arbitrary<'a> + PartialOrd + Copy + Int,
+        T: Int,
         S: ToString,
     {
         if range.start() > range.end() {
-            return Err(MutationError::User("Invalid range".to_string())).into();
+            return Err(MutationError::User("Invalid range".to_string()));
         } else if range.start() == range.end() {
-            return Ok(*range.start()).into();
+            return Ok(*range.start());
+        }
+        if self.check {
+            return Err(self.failure(err()));
+        }
+        if self.is_empty() {
+            return Err(MutationError::Exhausted);
+        }
+
+        let start = range.start().to_unsigned();
+        let delta = range.end().to_unsigned().wrapping_sub(start) & T::MASK;
+        let size = (T::MASK.count_ones() / 8) as usize;
+
+        if self.buffered() == 0 {
+            if let Some(offset) = self.source.choice(delta) {
+                self.consumed += 1;
+                return Ok(T::from_unsigned(start.wrapping_add(offset) & T::MASK));
+            }
         }
-        if !self.check && self.arb.is_empty() {
-            return Err(MutationError::User("Ran out of entropy".to_string())).into();
+
+        let mut arbitrary_int = 0u128;
+        let mut bytes_consumed = 0;
+        while bytes_consumed < size && (delta >> (bytes_consumed * 8)) > 0 {
+            let byte = match self.next_byte() {
+                None => break,
+                Some(b) => b,
+            };
+            bytes_consumed += 1;
+            arbitrary_int = (arbitrary_int << 8) | byte as u128;
         }
-        self.with(err, |u| u.int_in_range(range))
+
+        let offset = if delta == T::MASK {
+            arbitrary_int
+        } else {
+            arbitrary_int % (delta + 1)
+        };
+        Ok(T::from_unsigned(start.wrapping_add(offset) & T::MASK))
     }
 
     /// Call the specified Arbitrary function in mutation mode, or produce an error in check mode.
+    #[cfg(feature = "arbitrary")]
     pub fn with<T, S: ToString>(
         &mut self,
         err: impl FnOnce() -> S,
-        f: impl FnOnce(&mut Unstructured<'a>) -> Result<T, arbitrary::Error>,
+        f: impl FnOnce(&mut arbitrary::Unstructured<'_>) -> Result<T, arbitrary::Error>,
     ) -> Mutation<T> {
         if self.check {
-            Err(MutationError::Check(err().to_string())).into()
+            return Err(self.failure(err()));
+        }
+
+        // A finite source is handed over whole, as arbitrary expects of its input,
+        // so that collections are not capped by the chunk size. An endless one is
+        // topped up to a chunk, with the unused bytes moved to the front.
+        let have = self.buffered();
+        let want = match self.source.remaining() {
+            Some(remaining) => remaining,
+            None => ARBITRARY_CHUNK.saturating_sub(have),
+        };
+        if want > 0 {
+            self.buffer.drain(..self.cursor);
+            self.cursor = 0;
+            self.buffer.resize(have + want, 0);
+            let got = self.source.fill(&mut self.buffer[have..]);
+            self.buffer.truncate(have + got);
+        }
+
+        // arbitrary takes bytes from both ends of its input: values from the front,
+        // and collection lengths from the back. Whatever is left in the middle is
+        // still unused.
+        let data = &self.buffer[self.cursor..];
+        let mut u = arbitrary::Unstructured::new(data);
+        let result = f(&mut u);
+        let rest = u.len();
+        let front = u.peek_bytes(rest).map_or(data.len() - rest, |r| {
+            r.as_ptr() as usize - data.as_ptr() as usize
+        });
+        let back = data.len() - front - rest;
+        self.consumed += front + back;
+        self.buffer.truncate(self.cursor + front + rest);
+
+        // The source can only take back its most recent bytes, which are untouched
+        // only if nothing was taken from the back
+        if have == 0 && back == 0 && self.source.unfill(rest) {
+            self.buffer.clear();
+            self.cursor = 0;
         } else {
-            f(&mut self.arb).map_err(Into::into)
+            self.cursor += front;
         }
+        result.map_err(|e| match e {
+            arbitrary::Error::NotEnoughData => MutationError::Exhausted,
+            e => e.into(),
+        })
     }
 }
 
+/// Integer types which can be produced by [`Generator::int_in_range`]
+pub trait Int: Copy + PartialOrd {
+    /// A mask covering the bits of this type, when represented as a `u128`
+    #[doc(hidden)]
+    const MASK: u128;
+
+    /// The bits of this value, as the same-sized unsigned type
+    #[doc(hidden)]
+    fn to_unsigned(self) -> u128;
+
+    /// The inverse of `to_unsigned`
+    #[doc(hidden)]
+    fn from_unsigned(u: u128) -> Self;
+}
+
+macro_rules! impl_int {
+    ( $( $ty:ty : $unsigned:ty ; )* ) => {
+        $(
+            impl Int for $ty {
+                const MASK: u128 = <$unsigned>::MAX as u128;
+
+                fn to_unsigned(self) -> u128 {
+                    self as $unsigned as u128
+                }
+
+                fn from_unsigned(u: u128) -> Self {
+                    u as $unsigned as Self
+                }
+            }
+        )*
+    };
+}
+
+impl_int! {
+    u8: u8;
+    u16: u16;
+    u32: u32;
+    u64: u64;
+    u128: u128;
+    usize: usize;
+    i8: u8;
+    i16: u16;
+    i32: u32;
+    i64: u64;
+    i128: u128;
+    isize: usize;
+}
+
 #[cfg(test)]
+#[cfg(feature = "utils")]
+#[allow(missing_docs)]
 pub mod test {
     use crate::MutationError;
     use rand::prelude::SliceRandom;
@@ -156,6 +902,7 @@ pub mod test {
 
     /// Test that int_in_range won't accept an invalid range.
     #[test]
+    #[allow(clippy::reversed_empty_ranges)]
     pub fn test_generator_int_in_range_invalid_range() {
         let mut gen = crate::generator::Generator::from(&[0, 1, 2, 3, 4, 5][..]);
         assert_eq!(
@@ -172,7 +919,7 @@ pub mod test {
             assert_eq!(gen.int_in_range(5..=5, || "error").unwrap(), 5);
         }
         // The generator has not had any entropy consumed by this point.
-        assert_eq!(gen.len(), 1);
+        assert_eq!(gen.remaining(), Some(1));
     }
 
     /// Test that int_in_range will accept a valid range of multiple options.
@@ -183,10 +930,10 @@ pub mod test {
             assert_eq!(gen.int_in_range(0..=3, || "error").unwrap(), i % 4);
         }
         // The generator has no entropy remaining at this point.
-        assert_eq!(gen.len(), 0);
+        assert_eq!(gen.remaining(), Some(0));
         assert_eq!(
             gen.int_in_range(0..=3, || "error"),
-            Err(MutationError::User("Ran out of entropy".to_string()))
+            Err(MutationError::Exhausted)
         );
     }
 
@@ -224,7 +971,7 @@ pub mod test {
         // This is the only case where we can't choose a value, because we have 2 choices and 6 bytes.
         assert_eq!(
             gen.choose(&choices, || "error"),
-            Err(MutationError::User("Ran out of entropy".to_string()))
+            Err(MutationError::Exhausted)
         );
     }
 
@@ -243,7 +990,7 @@ pub mod test {
         // This is the only case where we can't choose a value, because we have 3 choices and 6 bytes.
         assert_eq!(
             gen.choose(&choices, || "error"),
-            Err(MutationError::User("Ran out of entropy".to_string()))
+            Err(MutationError::Exhausted)
         );
     }
 
@@ -270,7 +1017,187 @@ pub mod test {
         // This is the only case where we can't choose a value, because we have 3 choices and 6 bytes.
         assert_eq!(
             gen.choose(&choices, || "error"),
-            Err(MutationError::User("Ran out of entropy".to_string()))
+            Err(MutationError::Exhausted)
+        );
+    }
+
+    /// Test that a run driven by a random number generator can be replayed exactly.
+    #[test]
+    pub fn test_generator_replay() {
+        let log = crate::ReplayLog::new();
+        let rng = rand::rngs::StdRng::seed_from_u64(0);
+        let mut gen = crate::Generator::new(log.record(crate::RngSource(rng)));
+        let first: Vec<(u8, String)> = (0..10)
+            .map(|_| {
+                (
+                    gen.int_in_range(0..=9, || "error").unwrap(),
+                    gen.arbitrary(|| "error").unwrap(),
+                )
+            })
+            .collect();
+
+        let mut gen = crate::Generator::new(log.replay());
+        let second: Vec<(u8, String)> = (0..10)
+            .map(|_| {
+                (
+                    gen.int_in_range(0..=9, || "error").unwrap(),
+                    gen.arbitrary(|| "error").unwrap(),
+                )
+            })
+            .collect();
+
+        assert_eq!(first, second);
+    }
+
+    /// Test that entropy is accounted for, and that the fallback mode keeps going
+    /// deterministically once the source is exhausted.
+    #[test]
+    pub fn test_generator_exhausted_and_fallback() {
+        let mut gen = crate::generator::Generator::from(&[0, 1, 2][..]);
+        assert_eq!(gen.bytes(2, || "error").unwrap(), vec![0, 1]);
+        assert_eq!(gen.consumed(), 2);
+        assert_eq!(gen.remaining(), Some(1));
+        assert_eq!(gen.bytes(2, || "error"), Err(MutationError::Exhausted));
+
+        let mut gen = crate::generator::Generator::from(&[][..]);
+        assert_eq!(
+            gen.int_in_range(0..=9u8, || "error"),
+            Err(MutationError::Exhausted)
+        );
+
+        let run = || {
+            let mut gen = crate::generator::Generator::from(&[0, 1, 2][..]).with_fallback();
+            let xs: Vec<u32> = (0..100)
+                .map(|_| gen.int_in_range(0..=999, || "error").unwrap())
+                .collect();
+            let s: String = gen.arbitrary(|| "error").unwrap();
+            assert_eq!(gen.remaining(), None);
+            assert!(gen.consumed() > 3);
+            (xs, s)
+        };
+        assert_eq!(run(), run());
+    }
+
+    /// Test that bytes left over in the buffer by a source which can't take them
+    /// back are used in order, just as if they'd been given back.
+    #[test]
+    pub fn test_generator_buffer_without_unfill() {
+        struct Keep<'a>(&'a [u8]);
+
+        impl crate::Source for Keep<'_> {
+            fn fill(&mut self, buf: &mut [u8]) -> usize {
+                self.0.fill(buf)
+            }
+
+            fn remaining(&self) -> Option<usize> {
+                self.0.remaining()
+            }
+        }
+
+        let draw = |gen: &mut crate::Generator| {
+            let mut out = vec![];
+            for _ in 0..50 {
+                let x: u32 = gen.int_in_range(0..=999, || "error").unwrap();
+                let s: String = gen.arbitrary(|| "error").unwrap();
+                out.push((x, s, gen.consumed(), gen.remaining()));
+            }
+            out
+        };
+        let bytes = &crate::utils::NOISE[..10_000];
+        assert_eq!(
+            draw(&mut crate::Generator::new(Keep(bytes))),
+            draw(&mut crate::Generator::from(bytes))
+        );
+    }
+
+    /// Test that arbitrary values which take their lengths from the back of the
+    /// input draw on disjoint bytes, neither reusing nor skipping any.
+    #[test]
+    pub fn test_generator_arbitrary_disjoint() {
+        let bytes: Vec<u8> = (b'A'..=b'z').collect();
+        let mut gen = crate::Generator::from(&bytes[..]);
+        let a: String = gen.arbitrary(|| "error").unwrap();
+        let b: String = gen.arbitrary(|| "error").unwrap();
+        assert!(!a.is_empty() && !b.is_empty());
+
+        // The strings come from the front in order, and one length byte each from the back
+        let both = format!("{a}{b}");
+        assert_eq!(both.as_bytes(), &bytes[..both.len()]);
+        assert_eq!(gen.consumed(), both.len() + 2);
+        assert_eq!(gen.remaining(), Some(bytes.len() - both.len() - 2));
+        let x: u8 = gen.int_in_range(0..=255, || "error").unwrap();
+        assert_eq!(x, bytes[both.len()]);
+    }
+
+    /// Test the helpers for hand-written facts, in both modes.
+    #[test]
+    pub fn test_generator_helpers() {
+        let mut gen = crate::generator::Generator::from(&crate::utils::NOISE[..]);
+        let mut checker = crate::generator::Generator::checker();
+
+        let mut v = vec![1, 2, 3];
+        assert_eq!(
+            checker.set_with(&mut v, |v| v[0] = 0, || "error"),
+            Err(MutationError::Check("error".to_string()))
+        );
+        checker.set_with(&mut v, |v| v[0] = 1, || "error").unwrap();
+        gen.set_with(&mut v, |v| v[0] = 0, || "error").unwrap();
+        assert_eq!(v, vec![0, 2, 3]);
+
+        assert!(checker.truncate(&mut v, 2, || "error").is_err());
+        checker.truncate(&mut v, 3, || "error").unwrap();
+        gen.truncate(&mut v, 2, || -> &str { unreachable!() })
+            .unwrap();
+        assert_eq!(v, vec![0, 2]);
+
+        assert!(checker.push(&mut v, 4, || "error").is_err());
+        gen.push(&mut v, 4, || -> &str { unreachable!() }).unwrap();
+        assert_eq!(v, vec![0, 2, 4]);
+
+        assert!(checker.ensure(false, || "error", |_| Ok(())).is_err());
+        checker
+            .ensure(true, || "error", |_| unreachable!())
+            .unwrap();
+        gen.ensure(
+            false,
+            || "error",
+            |_| {
+                v.clear();
+                Ok(())
+            },
+        )
+        .unwrap();
+        assert!(v.is_empty());
+
+        let choices = || (0..3).map(|i| i.to_string());
+        assert!(checker.choose_owned(choices(), || "error").is_err());
+        let chosen = gen.choose_owned(choices(), || "error").unwrap();
+        assert!(choices().any(|c| c == chosen));
+    }
+
+    /// Test that edges are favored, but everything else is still possible.
+    #[test]
+    pub fn test_generator_int_biased_towards_edges() {
+        let mut gen = crate::generator::Generator::from(&crate::utils::NOISE[..]);
+        let xs: Vec<i32> = (0..1000)
+            .map(|_| {
+                gen.int_biased_towards_edges(-100..=100, || "error")
+                    .unwrap()
+            })
+            .collect();
+        let count = |x| xs.iter().filter(|&&y| y == x).count();
+        for edge in [-100, 0, 100] {
+            assert!(count(edge) > 100, "{} appeared {} times", edge, count(edge));
+        }
+        assert!(xs.iter().any(|x| ![-100, 0, 100].contains(x)));
+
+        assert_eq!(
+            gen.int_biased_towards_edges(5..=5u8, || "error").unwrap(),
+            5
+        );
+        assert_eq!(
+            crate::generator::Generator::checker().int_biased_towards_edges(0..=5u8, || "error"),
+            Err(MutationError::Check("error".to_string()))
         );
     }
 
@@ -284,6 +1211,6 @@ pub mod test {
         }
 
         // The generator has not had any entropy consumed by this point.
-        assert_eq!(gen.len(), 1);
+        assert_eq!(gen.remaining(), Some(1));
     }
 }
@@ -0,0 +1,189 @@
+use std::borrow::Borrow;
+use std::marker::PhantomData;
+
+use crate::*;
+
+/// An endless iterator of values built by one Fact, whose state carries over
+/// from each value to the next. See [`Fact::build_iter`].
+#[cfg(feature = "arbitrary")]
+pub struct BuildIter<'a, 'b, 'g, F, T>
+where
+    F: Fact<'a, T>,
+    T: Target<'a>,
+{
+    fact: F,
+    g: &'b mut Generator<'g>,
+    _phantom: PhantomData<&'a T>,
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a, 'b, 'g, F, T> BuildIter<'a, 'b, 'g, F, T>
+where
+    F: Fact<'a, T>,
+    T: Target<'a>,
+{
+    pub(crate) fn new(fact: F, g: &'b mut Generator<'g>) -> Self {
+        Self {
+            fact,
+            g,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// The fact, with whatever state it has reached so far
+    pub fn fact(&self) -> &F {
+        &self.fact
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a, 'b, 'g, F, T> Iterator for BuildIter<'a, 'b, 'g, F, T>
+where
+    F: Fact<'a, T>,
+    T: Target<'a> + for<'u> arbitrary::Arbitrary<'u>,
+{
+    type Item = T;
+
+    /// Build the next value, panicking on error like [`Fact::build`]
+    fn next(&mut self) -> Option<T> {
+        Some(crate::fact::build_arbitrary(&mut self.fact, self.g).unwrap())
+    }
+}
+
+/// An iterator which checks each of a stream of values against one Fact,
+/// whose state carries over from each value to the next. See [`Fact::check_iter`].
+pub struct CheckIter<'a, F, T, I>
+where
+    F: Fact<'a, T>,
+    T: Target<'a>,
+{
+    fact: F,
+    items: I,
+    _phantom: PhantomData<&'a T>,
+}
+
+impl<'a, F, T, I> CheckIter<'a, F, T, I>
+where
+    F: Fact<'a, T>,
+    T: Target<'a>,
+{
+    pub(crate) fn new(fact: F, items: I) -> Self {
+        Self {
+            fact,
+            items,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, F, T, I> Iterator for CheckIter<'a, F, T, I>
+where
+    F: Fact<'a, T>,
+    T: Target<'a>,
+    I: Iterator,
+    I::Item: Borrow<T>,
+{
+    type Item = Check;
+
+    fn next(&mut self) -> Option<Check> {
+        let item = self.items.next()?;
+        let mut g = Generator::checker();
+        let result = self.fact.mutate(&mut g, item.borrow().clone());
+        Some(g.into_check(result))
+    }
+}
+
+/// Check a sequence of values against one Fact, carrying the state of the fact
+/// over from each value to the next. Unlike wrapping the fact in
+/// [`vec`](crate::facts::vec), the values can come from any `IntoIterator`.
+///
+/// Each failure is prefixed with the index of the item it's about.
+///
+/// ```
+/// use contrafact::{*, facts::*};
+///
+/// let failures = check_seq([1, 2, 4, 5], consecutive_int_(1)).failures().unwrap().to_vec();
+/// assert_eq!(failures, vec!["[2]: consecutive_int", "[3]: consecutive_int"]);
+/// ```
+pub fn check_seq<'a, T, F, I>(items: I, fact: F) -> Check
+where
+    T: Target<'a>,
+    F: Fact<'a, T>,
+    I: IntoIterator,
+    I::Item: Borrow<T>,
+{
+    merge_item_checks(fact.check_iter(items))
+}
+
+/// Combine the checks of a sequence of items into one, prefixing each failure
+/// with the index of its item
+pub(crate) fn merge_item_checks(checks: impl IntoIterator<Item = Check>) -> Check {
+    checks
+        .into_iter()
+        .enumerate()
+        .map(|(i, check)| check.with_context(format_args!("[{}]", i)))
+        .collect()
+}
+
+/// Build a sequence of `num` values with one Fact, carrying the state of the
+/// fact over from each value to the next. See [`Fact::build_iter`].
+///
+/// ```
+/// use contrafact::{*, facts::*};
+///
+/// let mut g = utils::random_generator();
+/// let xs: Vec<u8> = build_seq(&mut g, 3, consecutive_int_(5));
+/// assert_eq!(xs, vec![5, 6, 7]);
+/// check_seq(&xs, consecutive_int_(5)).unwrap();
+/// ```
+#[cfg(feature = "arbitrary")]
+pub fn build_seq<'a, T, F>(g: &mut Generator<'_>, num: usize, fact: F) -> Vec<T>
+where
+    T: Target<'a> + for<'u> arbitrary::Arbitrary<'u>,
+    F: Fact<'a, T>,
+{
+    fact.build_n(g, num)
+}
+
+#[cfg(test)]
+#[cfg(feature = "utils")]
+mod tests {
+    use crate::facts::*;
+    use crate::*;
+
+    #[test]
+    fn test_build_iter_carries_state() {
+        let mut g = utils::random_generator();
+        let fact = consecutive_int_(10u32);
+
+        let xs: Vec<u32> = fact.clone().build_iter(&mut g).take(5).collect();
+        assert_eq!(xs, vec![10, 11, 12, 13, 14]);
+
+        assert_eq!(fact.build_n(&mut g, 3), vec![10, 11, 12]);
+    }
+
+    #[test]
+    fn test_check_iter() {
+        let fact = consecutive_int_(0u32);
+
+        let checks: Vec<Check> = fact.clone().check_iter([0, 1, 2, 3]).collect();
+        assert!(checks.iter().all(|c| c.is_ok()));
+
+        let checks: Vec<bool> = fact.check_iter(&[0, 1, 3, 3]).map(|c| c.is_ok()).collect();
+        // consecutive_int doesn't count a failing item, so it still expects 2 after it
+        assert_eq!(checks, vec![true, true, false, false]);
+    }
+
+    #[test]
+    fn test_check_seq_streams() {
+        let fact = facts![in_range("small", 0..10u32), consecutive_int_(7u32)];
+        let check = check_seq((7..).take_while(|x| *x < 12), fact);
+        assert_eq!(
+            check.failures().unwrap(),
+            &[
+                "[3]: small: expected 10 to be contained in 0..10".to_string(),
+                "[4]: small: expected 11 to be contained in 0..10".to_string(),
+            ]
+        );
+    }
+}
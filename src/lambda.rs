@@ -7,6 +7,7 @@ use crate::*;
 /// the [`Fact`] trait
 ///
 /// ```
+/// # #[cfg(feature = "utils")] {
 /// use contrafact::*;
 /// let mut g = utils::random_generator();
 ///
@@ -22,37 +23,59 @@ use crate::*;
 /// let list = fact.clone().build(&mut g);
 /// fact.check(&list).unwrap();
 /// assert_eq!(list, vec![2, 4, 8, 16]);
+/// # }
 /// ```
 pub fn lambda<'a, S, T>(
     label: impl ToString,
     state: S,
-    f: impl 'a + Send + Sync + Fn(&mut Generator<'a>, &mut S, T) -> Mutation<T>,
+    f: impl 'a + Send + Sync + Fn(&mut Generator<'_>, &mut S, T) -> Mutation<T>,
 ) -> Lambda<'a, S, T>
 where
     S: State,
     T: Target<'a>,
 {
     Lambda {
-        label: label.to_string(),
+        label: label.to_string().into(),
         state,
         fun: Arc::new(f),
+        snapshot: None,
+        describe: None,
+        stateless: None,
+        check_mut: None,
+        label_with: None,
+        labeled: false,
         _phantom: PhantomData,
     }
 }
 
-/// Create a lambda with unit state
+/// Create a lambda with unit state.
+///
+/// Such a lambda is taken to be stateless (see [`Fact::is_stateless`]), so it
+/// shouldn't hide any state inside its closure. Use [`lambda`] for a closure
+/// which does. A stateless lambda inside a combinator may also be checked on
+/// its own, so its closure can't rely on [`Generator::path`] when checking.
 pub fn lambda_unit<'a, T>(
     label: impl ToString,
-    f: impl 'a + Send + Sync + Fn(&mut Generator<'a>, T) -> Mutation<T>,
+    f: impl 'a + Send + Sync + Fn(&mut Generator<'_>, T) -> Mutation<T>,
 ) -> Lambda<'a, (), T>
 where
     T: Target<'a>,
 {
-    lambda(label, (), move |g, (), t| f(g, t))
+    lambda(label, (), move |g, (), t| f(g, t)).stateless_with(|()| true)
 }
 
 pub type LambdaFn<'a, S, T> =
-    Arc<dyn 'a + Send + Sync + Fn(&mut Generator<'a>, &mut S, T) -> Mutation<T>>;
+    Arc<dyn 'a + Send + Sync + Fn(&mut Generator<'_>, &mut S, T) -> Mutation<T>>;
+
+pub type SnapshotFn<'a, S> = Arc<dyn 'a + Send + Sync + Fn(&S) -> Snapshot<'a, S>>;
+
+pub type DescribeFn<'a, S> = Arc<dyn 'a + Send + Sync + Fn(&str, &S) -> Description>;
+
+pub type StatelessFn<'a, S> = Arc<dyn 'a + Send + Sync + Fn(&S) -> bool>;
+
+pub type CheckMutFn<'a, S, T> = Arc<dyn 'a + Send + Sync + Fn(&S, &mut T) -> Check>;
+
+pub type LabelFn<'a, S> = Arc<dyn 'a + Send + Sync + Fn(&str, &S) -> String>;
 
 #[derive(Clone)]
 pub struct Lambda<'a, S, T>
@@ -62,10 +85,168 @@ where
 {
     state: S,
     fun: LambdaFn<'a, S, T>,
-    label: String,
+    /// How to snapshot the state, if not by cloning it
+    snapshot: Option<SnapshotFn<'a, S>>,
+    /// How to describe this lambda, given its label and state
+    describe: Option<DescribeFn<'a, S>>,
+    /// Whether the state never changes, if it's known
+    stateless: Option<StatelessFn<'a, S>>,
+    /// How to check by reference, when stateless
+    check_mut: Option<CheckMutFn<'a, S, T>>,
+    /// How to label this lambda, given its label and state, unless it's been [`Fact::labeled`]
+    label_with: Option<LabelFn<'a, S>>,
+    /// Shared, so that cloning a fact doesn't copy it
+    label: Arc<str>,
+    /// Whether the label was given with [`Fact::labeled`], and so goes before each failure
+    labeled: bool,
     _phantom: PhantomData<&'a T>,
 }
 
+impl<'a, S, T> Lambda<'a, S, T>
+where
+    S: State,
+    T: Target<'a>,
+{
+    /// Snapshot the state with this function rather than by cloning it.
+    /// Combinators whose state is made of other facts use this to snapshot only
+    /// the state of those facts.
+    pub(crate) fn snapshot_with(
+        mut self,
+        f: impl 'a + Send + Sync + Fn(&S) -> Snapshot<'a, S>,
+    ) -> Self {
+        self.snapshot = Some(Arc::new(f));
+        self
+    }
+
+    /// Describe this lambda with a function of its label and state, rather
+    /// than by its label alone.
+    pub(crate) fn describe_with(
+        mut self,
+        f: impl 'a + Send + Sync + Fn(&str, &S) -> Description,
+    ) -> Self {
+        self.describe = Some(Arc::new(f));
+        self
+    }
+
+    /// Snapshot the state of the single fact which is this lambda's state
+    pub(crate) fn snapshot_inner<U>(self) -> Self
+    where
+        S: Fact<'a, U>,
+        U: Target<'a>,
+    {
+        self.snapshot_with(|fact| fact.save_state())
+    }
+
+    /// Decide whether this lambda is stateless with a function of its state.
+    /// Combinators use this to be stateless exactly when the facts inside them are.
+    /// Lambdas without one are taken to be stateful.
+    pub(crate) fn stateless_with(mut self, f: impl 'a + Send + Sync + Fn(&S) -> bool) -> Self {
+        self.stateless = Some(Arc::new(f));
+        self
+    }
+
+    /// Check by reference with this function of the state, whenever this lambda
+    /// is stateless. See [`Fact::check_mut`].
+    pub(crate) fn check_mut_with(
+        mut self,
+        f: impl 'a + Send + Sync + Fn(&S, &mut T) -> Check,
+    ) -> Self {
+        self.check_mut = Some(Arc::new(f));
+        self
+    }
+
+    /// Label this lambda with a function of its label and state, rather than by
+    /// its label alone, until it's given a label with [`Fact::labeled`].
+    pub(crate) fn label_with(mut self, f: impl 'a + Send + Sync + Fn(&str, &S) -> String) -> Self {
+        self.label_with = Some(Arc::new(f));
+        self
+    }
+
+    /// Label this lambda as `label/inner`, after the single fact which is its state
+    pub(crate) fn label_inner<U>(self) -> Self
+    where
+        S: Fact<'a, U>,
+        U: Target<'a>,
+    {
+        self.label_with(|label, fact| format!("{}/{}", label, fact.label()))
+    }
+
+    /// Stateless exactly when the single fact which is this lambda's state is
+    pub(crate) fn stateless_inner<U>(self) -> Self
+    where
+        S: Fact<'a, U>,
+        U: Target<'a>,
+    {
+        self.stateless_with(|fact| fact.is_stateless())
+    }
+
+    /// Whether to check by reading through references, rather than by mutation.
+    ///
+    /// A label goes before each failure as it's made, and the tracing spans need
+    /// the path, both of which take a Generator, so those checks run by mutation.
+    fn borrows(&self) -> bool
+    where
+        S: 'a + Debug,
+    {
+        self.check_mut.is_some()
+            && self.is_stateless()
+            && !self.labeled
+            && !tracing::enabled!(tracing::Level::TRACE)
+    }
+
+    /// Check a value by running a mutation of it on a clone of this lambda
+    fn check_by_mutation(&self, t: T) -> Check
+    where
+        S: 'a + Debug,
+    {
+        let mut g = Generator::checker();
+        let result = self.clone().mutate(&mut g, t);
+        g.into_check(result)
+    }
+}
+
+impl<'a, A, B, T> Lambda<'a, (A, B), T>
+where
+    A: Fact<'a, T>,
+    B: Fact<'a, T>,
+    T: Target<'a>,
+{
+    /// Snapshot the state of both of the facts which are this lambda's state
+    pub(crate) fn snapshot_pair(self) -> Self {
+        self.snapshot_with(|(a, b)| {
+            let (a, b) = (a.save_state(), b.save_state());
+            Snapshot::new(move |(x, y): &mut (A, B)| {
+                x.restore_state(&a);
+                y.restore_state(&b);
+            })
+        })
+    }
+}
+
+#[cfg(feature = "json")]
+impl<'a, F, T> Lambda<'a, Vec<F>, T>
+where
+    F: Fact<'a, T>,
+    T: Target<'a>,
+{
+    /// Snapshot the state of each of the facts which are this lambda's state
+    pub(crate) fn snapshot_each(self) -> Self {
+        self.snapshot_with(|facts| {
+            let snapshots: Vec<_> = facts.iter().map(|f| f.save_state()).collect();
+            Snapshot::new(move |facts: &mut Vec<F>| {
+                for (f, s) in facts.iter_mut().zip(snapshots.iter()) {
+                    f.restore_state(s);
+                }
+            })
+        })
+    }
+}
+
+/// The label of a combinator made of several facts, as `parent/{a, b}`
+pub(crate) fn label_children(parent: &str, children: &[String]) -> String {
+    format!("{}/{{{}}}", parent, children.join(", "))
+}
+
 /// A Lambda with unit state
 pub type LambdaUnit<'a, T> = Lambda<'a, (), T>;
 
@@ -84,24 +265,101 @@ where
 
 impl<'a, S, T> Fact<'a, T> for Lambda<'a, S, T>
 where
-    S: State + Debug,
+    S: 'a + State + Debug,
     T: Target<'a>,
 {
-    fn mutate(&mut self, g: &mut Generator<'a>, t: T) -> Mutation<T> {
-        (self.fun)(g, &mut self.state, t)
+    fn describe(&self) -> Description {
+        match &self.describe {
+            Some(describe) => describe(&self.label, &self.state),
+            None => Description::new("fact", &self.label),
+        }
+    }
+
+    fn is_stateless(&self) -> bool {
+        self.stateless
+            .as_ref()
+            .is_some_and(|stateless| stateless(&self.state))
+    }
+
+    fn check_mut(&self, t: &mut T) -> Check {
+        match &self.check_mut {
+            Some(check_mut) if self.borrows() => check_mut(&self.state, t),
+            _ => self.check_by_mutation(t.clone()),
+        }
+    }
+
+    fn check_ref(&self, t: &T) -> Check {
+        match &self.check_mut {
+            Some(_) if self.borrows() => self.check_mut(&mut t.clone()),
+            // Without a way to check by reference, clone the value just once, for the mutation
+            _ => self.check_by_mutation(t.clone()),
+        }
+    }
+
+    fn save_state(&self) -> Snapshot<'a, Self> {
+        let state = match &self.snapshot {
+            Some(snapshot) => snapshot(&self.state),
+            None => {
+                let state = self.state.clone();
+                Snapshot::new(move |s| *s = state.clone())
+            }
+        };
+        Snapshot::new(move |lambda: &mut Self| state.restore(&mut lambda.state))
+    }
+
+    fn mutate(&mut self, g: &mut Generator<'_>, t: T) -> Mutation<T> {
+        let span = tracing::trace_span!(
+            "mutate",
+            label = %self.label,
+            path = %g.path(),
+            mode = g.mode()
+        );
+        let _enter = span.enter();
+
+        // Values only change during mutation, so only keep the old one around to
+        // compare against if there's somebody listening.
+        let before = (!g.is_checking()
+            && (g.is_recording() || tracing::enabled!(tracing::Level::TRACE)))
+        .then(|| t.clone());
+        let recorded = g.changes_recorded();
+        let t = if self.labeled {
+            g.with_label(&self.label, |g| (self.fun)(g, &mut self.state, t))?
+        } else {
+            (self.fun)(g, &mut self.state, t)?
+        };
+        if let Some(before) = before {
+            if before != t {
+                tracing::trace!(old = ?before, new = ?t, "changed");
+                // Only the innermost fact which made a change is responsible for it
+                if g.changes_recorded() == recorded {
+                    g.record_change(Change {
+                        path: g.path(),
+                        label: self.label.to_string(),
+                        old: format!("{:?}", before),
+                        new: format!("{:?}", t),
+                    });
+                }
+            }
+        }
+        Ok(t)
     }
 
     fn label(&self) -> String {
-        self.label.clone()
+        match &self.label_with {
+            Some(label_with) if !self.labeled => label_with(&self.label, &self.state),
+            _ => self.label.to_string(),
+        }
     }
 
     fn labeled(mut self, label: impl ToString) -> Self {
-        self.label = label.to_string();
+        self.label = label.to_string().into();
+        self.labeled = true;
         self
     }
 }
 
 #[test]
+#[cfg(feature = "utils")]
 fn test_lambda_fact() {
     use crate::facts::*;
     let mut g = utils::random_generator();
@@ -130,3 +388,56 @@ fn test_lambda_fact() {
         fact(3, 4).check(&list).unwrap();
     }
 }
+
+#[test]
+#[cfg(feature = "utils")]
+fn test_labels() {
+    use crate::facts::*;
+
+    #[derive(Debug, Clone, PartialEq, arbitrary::Arbitrary)]
+    struct S {
+        x: u32,
+    }
+
+    let x = || lens1("S::x", |s: &mut S| &mut s.x, eq(1));
+    assert_eq!(x().label(), "S::x");
+    assert_eq!(vec(x()).label(), "vec/S::x");
+    assert_eq!(and(eq(1), eq(2)).label(), "and/{eq(1), eq(2)}");
+    assert_eq!(not(eq(1)).label(), "not/eq(1)");
+    assert_eq!(guard(eq(1), eq(2)).label(), "guard/{eq(1), eq(2)}");
+    assert_eq!(
+        when(eq(1), eq(2), eq(3)).label(),
+        "when/{eq(1), eq(2), eq(3)}"
+    );
+    assert_eq!(vec(x()).labeled("xs").label(), "xs");
+    assert_eq!(brute("is even", |x: &u8| x.is_multiple_of(2)).label(), "is even");
+
+    // Each failure has only the nearest label, ahead of the path within its fact
+    let fact = facts![vec_len(3), vec(x().labeled("one"))].labeled("ones");
+    let ss = vec![S { x: 1 }, S { x: 2 }];
+    let expected = vec![
+        "ones: vec should be of length 3 but is actually of length 2".to_string(),
+        "[1]: one: S::x: expected 2 == 1".to_string(),
+    ];
+    assert_eq!(fact.clone().check(&ss).failures().unwrap(), expected);
+    assert_eq!(
+        fact.check_mut(&mut ss.clone()).failures().unwrap(),
+        expected
+    );
+    let mut g = Generator::checker();
+    let result = fact.clone().mutate(&mut g, ss.clone());
+    assert_eq!(g.into_check(result).failures().unwrap(), expected);
+
+    // Within a fact which isn't labeled, a labeled fact is still checked by reference
+    let fact = vec(x().labeled("one"));
+    assert_eq!(
+        fact.check_mut(&mut ss.clone()).failures().unwrap(),
+        &["[1]: one: S::x: expected 2 == 1".to_string()]
+    );
+
+    let brute = brute("is even", |x: &u8| x.is_multiple_of(2));
+    assert_eq!(
+        brute.check(&3).failures().unwrap(),
+        &["is even".to_string()]
+    );
+}
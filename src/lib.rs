@@ -11,6 +11,7 @@
 //! meets the constraint, or to generate new instances of `S` which meet the constraint.
 //!
 //! ```
+//! # #[cfg(feature = "utils")] {
 //! use contrafact::{Fact, facts::{eq, lens1}};
 //! use arbitrary::{Arbitrary, Unstructured};
 //!
@@ -29,6 +30,7 @@
 //! let mut g = contrafact::utils::random_generator();
 //! let a = fact.build(&mut g);
 //! assert_eq!(a.x, 1);
+//! # }
 //! ```
 //!
 //! ## Things to know
@@ -37,13 +39,16 @@
 //! crate. You can also define your own facts by hand by implementing the `Fact`
 //! trait. *TODO: example of this.*
 //!
-//! `contrafact` leans heavily on the [`arbitrary`](https://docs.rs/arbitrary/1.0.0/arbitrary/) crate for
-//! generating arbitrary data. Get to know this library, because you will need to implement `Arbitrary` for any
-//! type you wish to write a [`Fact`](crate::Fact) about.
+//! By default, `contrafact` leans heavily on the [`arbitrary`](https://docs.rs/arbitrary/1.0.0/arbitrary/) crate for
+//! generating arbitrary data. Get to know this library, because you will want to implement `Arbitrary` for any
+//! type you wish to build with a [`Fact`]. Types which don't implement `Arbitrary` can still be
+//! checked, and can be built via [`Fact::build_with`] using a custom generation function. Support for
+//! `arbitrary` can be turned off entirely by disabling the default `arbitrary` feature.
 //!
 //! Facts can be used to check if a constraint is matched via [`Fact::check()`] or [`check_seq`],
 //! and also to build new values via [`Fact::build`] and [`build_seq`]. Building values requires
-//! the use of `arbitrary::Unstructured`.
+//! a [`Generator`], which draws its entropy from some [`Source`]: a byte slice from a fuzzer,
+//! a random number generator, or a [`ReplayLog`] of a previous run.
 //!
 //! Facts can also be stateful, such that the constraint changes while checking or building a sequence. *TODO: example of stateful fact.*
 //!
@@ -59,48 +64,76 @@
 #![warn(missing_docs)]
 
 mod check;
+mod coverage;
+mod description;
+#[cfg(feature = "arbitrary")]
+mod enumerate;
 mod error;
 mod fact;
 /// Some built-in implementations of some useful facts
 pub mod facts;
 mod generator;
+mod iter;
 mod lambda;
+mod report;
+mod source;
+mod validator;
 pub use facts::*;
 
 #[cfg(feature = "utils")]
 pub mod utils;
 
+#[cfg(feature = "arbitrary")]
 pub use arbitrary;
 
 pub use check::Check;
+pub use coverage::{Coverage, CoveragePoint, CoverageSummary};
+pub use description::{Constraint, Contradiction, Description};
+#[cfg(feature = "arbitrary")]
+pub use enumerate::Enumerate;
 pub use error::*;
-pub use fact::{Fact, State, Target};
+pub use fact::{collect_checks, Fact, Snapshot, State, Target};
 pub use generator::*;
+#[cfg(feature = "arbitrary")]
+pub use iter::{build_seq, BuildIter};
+pub use iter::{check_seq, CheckIter};
 pub use lambda::{lambda, lambda_unit};
+pub use report::*;
+pub use source::*;
+pub use validator::FactValidator;
 
-pub(crate) use lambda::{Lambda, LambdaUnit};
+pub(crate) use lambda::{label_children, Lambda, LambdaUnit};
 
 pub use either;
 
 /// The `brute` fact should only make this many attempts
+#[cfg(feature = "arbitrary")]
 pub(crate) const BRUTE_ITERATION_LIMIT: usize = 1000;
 
 /// When running `Fact::satisfy`, repeat mutate+check this many times, in case
 /// repetition helps ease into the constraint.
 pub(crate) const SATISFY_ATTEMPTS: usize = 100;
 
+/// When running `Fact::satisfy`, if this many attempts in a row produce a value
+/// which was produced by one of the few attempts before, the facts are considered
+/// to be in conflict, and `satisfy` gives up early if it can find the facts
+/// overwriting or contradicting each other.
+pub(crate) const CONFLICT_REPEATS: usize = 5;
+
 /// Convenience macro for creating a collection of [`Fact`](crate::Fact)s
 /// of different types.
 /// The Facts will be composed into a nested series of [`AndFact`] which causes
 /// all facts to be applied in sequence. The collection of Facts is also a Fact.
 ///
 /// ```
+/// # #[cfg(feature = "utils")] {
 /// use contrafact::*;
 ///
 /// let eq1 = eq(1);
 /// let not2 = not(eq(2));
 /// let mut fact = facts![eq1, not2];
 /// assert!(fact.check(&1).is_ok());
+/// # }
 /// ```
 #[macro_export]
 macro_rules! facts {
@@ -0,0 +1,225 @@
+use crate::{Contradiction, Failure};
+
+/// A change made to some part of a value by a single fact during `satisfy`.
+/// See [`Fact::satisfy_with_report`](crate::Fact::satisfy_with_report).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Change {
+    /// The path to the part of the value which changed, as in [`Generator::path`](crate::Generator::path)
+    pub path: String,
+    /// The label of the fact which made the change
+    pub label: String,
+    /// The `Debug` representation of the old value
+    pub old: String,
+    /// The `Debug` representation of the new value
+    pub new: String,
+}
+
+/// Two facts which changed the same part of a value during a single attempt,
+/// so that the second undid the work of the first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Overwrite {
+    /// The path to the part of the value changed by the first fact
+    pub path: String,
+    /// The label of the fact whose change was overwritten
+    pub first: String,
+    /// The label of the fact which overwrote it
+    pub second: String,
+}
+
+/// One mutate-then-check attempt made by `satisfy`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Attempt {
+    /// Every change made during the mutation, in order
+    pub changes: Vec<Change>,
+    /// Changes which were overwritten by a different fact during the mutation
+    pub overwrites: Vec<Overwrite>,
+    /// The check failures for the mutated value. Empty for a successful attempt.
+    pub failures: Vec<Failure>,
+}
+
+impl Attempt {
+    pub(crate) fn new(changes: Vec<Change>, failures: Vec<Failure>) -> Self {
+        let mut overwrites = vec![];
+        for (i, first) in changes.iter().enumerate() {
+            let overwritten = changes[i + 1..]
+                .iter()
+                .find(|second| second.label != first.label && overlaps(&first.path, &second.path));
+            if let Some(second) = overwritten {
+                overwrites.push(Overwrite {
+                    path: first.path.clone(),
+                    first: first.label.clone(),
+                    second: second.label.clone(),
+                });
+            }
+        }
+        Self {
+            changes,
+            overwrites,
+            failures,
+        }
+    }
+}
+
+/// Whether one path is within the other
+fn overlaps(a: &str, b: &str) -> bool {
+    let within = |inner: &str, outer: &str| {
+        outer.is_empty()
+            || inner == outer
+            || inner.starts_with(outer) && inner[outer.len()..].starts_with('/')
+    };
+    within(a, b) || within(b, a)
+}
+
+/// What happened during a call to [`Fact::satisfy_with_report`](crate::Fact::satisfy_with_report)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SatisfyReport {
+    /// Every attempt, in order. If the constraint was satisfied, the last attempt succeeded.
+    pub attempts: Vec<Attempt>,
+}
+
+impl SatisfyReport {
+    /// All changes made during all attempts
+    pub fn changes(&self) -> impl Iterator<Item = &Change> {
+        self.attempts.iter().flat_map(|a| a.changes.iter())
+    }
+
+    /// The indices of attempts in which facts overwrote each other's changes.
+    /// If many attempts are flagged, the facts are probably fighting.
+    pub fn conflicting_attempts(&self) -> Vec<usize> {
+        self.attempts
+            .iter()
+            .enumerate()
+            .filter(|(_, a)| !a.overwrites.is_empty())
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+/// A diagnosis of facts which keep undoing each other's changes, found when
+/// `satisfy` keeps producing the same values over and over.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conflict {
+    /// Changes by one fact which were overwritten by another during a single attempt
+    pub overwrites: Vec<Overwrite>,
+    /// Simple facts which obviously contradict each other.
+    /// See [`Description::contradictions`](crate::Description::contradictions).
+    pub contradictions: Vec<Contradiction>,
+    /// The check failures of the last attempt
+    pub failures: Vec<Failure>,
+}
+
+impl std::fmt::Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Facts are in conflict, so the constraint can't be satisfied."
+        )?;
+        for c in self.contradictions.iter() {
+            writeln!(f, "- at {:?}, {} contradicts {}", c.path, c.first, c.second)?;
+        }
+        for o in self.overwrites.iter() {
+            writeln!(
+                f,
+                "- at {:?}, {} was overwritten by {}",
+                o.path, o.first, o.second
+            )?;
+        }
+        write!(f, "Last check failure: {:?}", self.failures)
+    }
+}
+
+#[test]
+#[cfg(feature = "utils")]
+fn test_conflict() {
+    use crate::{facts::*, *};
+    let mut g = utils::random_generator();
+
+    let mut fact = facts![
+        lens1("0", |t: &mut (u8, u8)| &mut t.0, eq(1)),
+        lens1("1", |t: &mut (u8, u8)| &mut t.1, eq(3)),
+        lens1("0", |t: &mut (u8, u8)| &mut t.0, eq(2)),
+    ];
+    let Err(ContrafactError::Conflict(conflict)) = fact.satisfy(&mut g, (0, 0)) else {
+        panic!("expected a conflict");
+    };
+    println!("{}", conflict);
+    assert_eq!(conflict.overwrites.len(), 1);
+    assert_eq!(conflict.contradictions.len(), 1);
+    assert_eq!(conflict.contradictions[0].path, "0");
+    assert_eq!(conflict.failures.len(), 1);
+}
+
+#[test]
+#[cfg(feature = "utils")]
+fn test_repeats_without_conflict() {
+    use crate::{facts::*, *};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    /// Fails the first few checks of any value, without ever changing it
+    #[derive(Clone, Debug)]
+    struct Flaky(Arc<AtomicUsize>);
+
+    impl<'a> Fact<'a, u8> for Flaky {
+        fn labeled(self, _: impl ToString) -> Self {
+            self
+        }
+
+        fn label(&self) -> String {
+            "Flaky".to_string()
+        }
+
+        fn mutate(&mut self, g: &mut Generator<'_>, t: u8) -> Mutation<u8> {
+            if g.is_checking() && self.0.fetch_add(1, Ordering::SeqCst) < 20 {
+                g.fail("not yet")?;
+            }
+            Ok(t)
+        }
+    }
+
+    let mut g = utils::random_generator();
+    let mut fact = facts![eq(1u8), Flaky(Arc::new(AtomicUsize::new(0)))];
+    assert_eq!(fact.satisfy(&mut g, 0).unwrap(), 1);
+}
+
+#[test]
+#[cfg(feature = "utils")]
+fn test_overlaps() {
+    assert!(overlaps("", "a/b"));
+    assert!(overlaps("a", "a/b"));
+    assert!(overlaps("a/b", "a/b"));
+    assert!(!overlaps("a/b", "a/bc"));
+    assert!(!overlaps("[0]", "[1]"));
+}
+
+#[test]
+#[cfg(feature = "utils")]
+fn test_report_overwrites() {
+    use crate::{facts::*, *};
+    let mut g = utils::random_generator();
+
+    let mut fact = facts![
+        lens1("0", |t: &mut (u8, u8)| &mut t.0, eq(1)),
+        lens1("0", |t: &mut (u8, u8)| &mut t.0, eq(2)),
+        lens1("1", |t: &mut (u8, u8)| &mut t.1, eq(3)),
+    ];
+    let (result, report) = fact.satisfy_with_report(&mut g, (0, 0));
+    assert!(matches!(result, Err(ContrafactError::Conflict(_))));
+    assert!(report.attempts.len() < SATISFY_ATTEMPTS);
+    assert_eq!(report.conflicting_attempts().len(), report.attempts.len());
+    assert_eq!(
+        report.attempts[0].overwrites,
+        vec![Overwrite {
+            path: "0".to_string(),
+            first: "eq(1)".to_string(),
+            second: "eq(2)".to_string(),
+        }]
+    );
+    assert_eq!(
+        report.changes().filter(|c| c.path == "1").count(),
+        1,
+        "the second field only changes once"
+    );
+}
@@ -0,0 +1,359 @@
+//! Sources of raw entropy for a [`Generator`](crate::Generator).
+//!
+//! A Generator does not care where its bytes come from. They can come from a
+//! fuzzer via a byte slice or an `arbitrary::Unstructured`, from a random
+//! number generator, or from a [`ReplayLog`] of a previous run.
+
+use std::sync::{Arc, Mutex};
+
+/// A source of raw bytes, from which a [`Generator`](crate::Generator) builds
+/// all of its data.
+pub trait Source {
+    /// Fill as much of the buffer as possible with fresh bytes, returning the
+    /// number of bytes written. Fewer bytes than requested are only written
+    /// when the source is exhausted.
+    fn fill(&mut self, buf: &mut [u8]) -> usize;
+
+    /// The number of bytes left in this source, or `None` if there is no limit.
+    fn remaining(&self) -> Option<usize>;
+
+    /// Choose a number from `0..=max` directly, rather than from raw bytes.
+    ///
+    /// Sources which walk the tree of choices made by a Generator, like an
+    /// [`Enumeration`], need to know how many options each choice has.
+    /// Other sources return `None`, and the choice is made from raw bytes.
+    fn choice(&mut self, _max: u128) -> Option<u128> {
+        None
+    }
+
+    /// Give back this many of the bytes most recently drawn by `fill`, because
+    /// they went unused. Returns false if this source doesn't support it, in
+    /// which case the Generator keeps them for later.
+    fn unfill(&mut self, _n: usize) -> bool {
+        false
+    }
+}
+
+impl<S: Source + ?Sized> Source for Box<S> {
+    fn fill(&mut self, buf: &mut [u8]) -> usize {
+        (**self).fill(buf)
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        (**self).remaining()
+    }
+
+    fn choice(&mut self, max: u128) -> Option<u128> {
+        (**self).choice(max)
+    }
+
+    fn unfill(&mut self, n: usize) -> bool {
+        (**self).unfill(n)
+    }
+}
+
+impl<S: Source + ?Sized> Source for &mut S {
+    fn fill(&mut self, buf: &mut [u8]) -> usize {
+        (**self).fill(buf)
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        (**self).remaining()
+    }
+
+    fn choice(&mut self, max: u128) -> Option<u128> {
+        (**self).choice(max)
+    }
+
+    fn unfill(&mut self, n: usize) -> bool {
+        (**self).unfill(n)
+    }
+}
+
+impl Source for &[u8] {
+    fn fill(&mut self, buf: &mut [u8]) -> usize {
+        let n = buf.len().min(self.len());
+        let (head, tail) = self.split_at(n);
+        buf[..n].copy_from_slice(head);
+        *self = tail;
+        n
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> Source for arbitrary::Unstructured<'a> {
+    fn fill(&mut self, buf: &mut [u8]) -> usize {
+        let n = buf.len().min(self.len());
+        let bytes = self.bytes(n).expect("length was checked");
+        buf[..n].copy_from_slice(bytes);
+        n
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+/// A never-ending Source backed by a random number generator.
+///
+/// ```
+/// use contrafact::*;
+/// use rand::SeedableRng;
+///
+/// let rng = rand::rngs::StdRng::seed_from_u64(42);
+/// let mut g = Generator::new(RngSource(rng));
+/// let x: u8 = g.int_in_range(0..=9, || "unreachable").unwrap();
+/// assert!(x < 10);
+/// ```
+#[cfg(feature = "rand")]
+#[derive(Clone, Debug)]
+pub struct RngSource<R>(pub R);
+
+#[cfg(feature = "rand")]
+impl<R: rand::RngCore> Source for RngSource<R> {
+    fn fill(&mut self, buf: &mut [u8]) -> usize {
+        self.0.fill_bytes(buf);
+        buf.len()
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// A record of every byte drawn from some other Source, which can be used
+/// to reproduce a run exactly.
+///
+/// ```
+/// # #[cfg(feature = "utils")] {
+/// use contrafact::*;
+///
+/// let log = ReplayLog::new();
+/// let mut g = Generator::new(log.record(&utils::NOISE[..]));
+/// let first: Vec<u32> = (0..5).map(|_| g.int_in_range(0..=999, || "").unwrap()).collect();
+///
+/// let mut g = Generator::new(log.replay());
+/// let second: Vec<u32> = (0..5).map(|_| g.int_in_range(0..=999, || "").unwrap()).collect();
+///
+/// assert_eq!(first, second);
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ReplayLog(Arc<Mutex<Vec<u8>>>);
+
+impl ReplayLog {
+    /// Create an empty log
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wrap a Source so that every byte drawn from it is appended to this log
+    pub fn record<S: Source>(&self, source: S) -> Recording<S> {
+        Recording {
+            source,
+            log: self.clone(),
+        }
+    }
+
+    /// Create a Source which produces the recorded bytes, in order
+    pub fn replay(&self) -> Replay {
+        Replay {
+            bytes: self.bytes(),
+            position: 0,
+        }
+    }
+
+    /// All bytes recorded so far
+    pub fn bytes(&self) -> Vec<u8> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+/// A Source which records all bytes drawn from it into a [`ReplayLog`].
+/// Use [`ReplayLog::record`] to construct.
+#[derive(Debug)]
+pub struct Recording<S> {
+    source: S,
+    log: ReplayLog,
+}
+
+impl<S: Source> Source for Recording<S> {
+    fn fill(&mut self, buf: &mut [u8]) -> usize {
+        let n = self.source.fill(buf);
+        self.log.0.lock().unwrap().extend_from_slice(&buf[..n]);
+        n
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        self.source.remaining()
+    }
+}
+
+/// A Source which plays back the contents of a [`ReplayLog`].
+/// Use [`ReplayLog::replay`] to construct.
+#[derive(Clone, Debug)]
+pub struct Replay {
+    bytes: Vec<u8>,
+    position: usize,
+}
+
+impl Source for Replay {
+    fn fill(&mut self, buf: &mut [u8]) -> usize {
+        let mut rest = &self.bytes[self.position..];
+        let n = rest.fill(buf);
+        self.position += n;
+        n
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        Some(self.bytes.len() - self.position)
+    }
+}
+
+/// A never-ending Source which draws from another Source until it is exhausted,
+/// and then continues with bytes derived by hashing everything drawn so far.
+/// Use [`Generator::with_fallback`](crate::Generator::with_fallback) to construct.
+#[derive(Clone, Debug)]
+pub struct Fallback<S> {
+    source: S,
+    state: u64,
+}
+
+impl<S: Source> Fallback<S> {
+    /// FNV-1a offset basis
+    const SEED: u64 = 0xcbf2_9ce4_8422_2325;
+    /// FNV-1a prime
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    /// Wrap a Source so that it never runs out
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            state: Self::SEED,
+        }
+    }
+
+    /// The next word of derived entropy, via splitmix64
+    fn next_word(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+impl<S: Source> Source for Fallback<S> {
+    fn fill(&mut self, buf: &mut [u8]) -> usize {
+        let n = self.source.fill(buf);
+        for b in &buf[..n] {
+            self.state = (self.state ^ *b as u64).wrapping_mul(Self::PRIME);
+        }
+        for chunk in buf[n..].chunks_mut(8) {
+            let word = self.next_word().to_le_bytes();
+            chunk.copy_from_slice(&word[..chunk.len()]);
+        }
+        buf.len()
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// A Source which walks every path through the tree of choices made by a
+/// Generator, one path per run, in depth-first order.
+///
+/// Each choice made with [`Generator::int_in_range`](crate::Generator::int_in_range)
+/// (and so also `choose`) has as many branches as there are options, and each raw
+/// byte drawn has 256. Choices which were never made on a run are left out of the
+/// tree, so the walk only covers choices which actually matter.
+/// See [`Fact::enumerate`](crate::Fact::enumerate).
+///
+/// ```
+/// use contrafact::*;
+///
+/// let mut e = Enumeration::new();
+/// let mut seen = vec![];
+/// loop {
+///     let mut g = Generator::new(&mut e);
+///     let a: u8 = g.int_in_range(0..=1, || "").unwrap();
+///     let b: u8 = g.int_in_range(0..=2, || "").unwrap();
+///     seen.push((a, b));
+///     drop(g);
+///     if !e.next_path() {
+///         break;
+///     }
+/// }
+/// assert_eq!(seen, vec![(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2)]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Enumeration {
+    /// The choices made along the current path, with the maximum for each
+    path: Vec<(u128, u128)>,
+    /// How many choices have been made on the current run
+    position: usize,
+}
+
+impl Enumeration {
+    /// Start at the first path, where every choice is 0
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The next choice on the current path
+    fn draw(&mut self, max: u128) -> u128 {
+        let c = match self.path.get_mut(self.position) {
+            Some(choice) => {
+                *choice = (choice.0.min(max), max);
+                choice.0
+            }
+            None => {
+                self.path.push((0, max));
+                0
+            }
+        };
+        self.position += 1;
+        c
+    }
+
+    /// Move on to the next path, to be walked by the next run.
+    /// Returns false once every path has been walked.
+    pub fn next_path(&mut self) -> bool {
+        self.path.truncate(self.position);
+        self.position = 0;
+        while let Some((c, max)) = self.path.pop() {
+            if c < max {
+                self.path.push((c + 1, max));
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl Source for Enumeration {
+    fn fill(&mut self, buf: &mut [u8]) -> usize {
+        for b in buf.iter_mut() {
+            *b = self.draw(u8::MAX as u128) as u8;
+        }
+        buf.len()
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        None
+    }
+
+    fn choice(&mut self, max: u128) -> Option<u128> {
+        Some(self.draw(max))
+    }
+
+    fn unfill(&mut self, n: usize) -> bool {
+        self.position -= n;
+        true
+    }
+}
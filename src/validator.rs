@@ -0,0 +1,110 @@
+use std::marker::PhantomData;
+
+use crate::*;
+
+/// Holds a Fact and its state, for checking values one at a time as they
+/// arrive, e.g. from a channel.
+///
+/// As with [`Fact::satisfy`], the state of the fact only advances when a value
+/// passes, so one bad value doesn't throw off the checks of the values after it.
+///
+/// ```
+/// use contrafact::{*, facts::*};
+///
+/// let mut v = FactValidator::new(consecutive_int_(1));
+/// assert!(v.push(&1).is_ok());
+/// assert!(v.push(&5).is_err());
+/// assert!(v.push(&2).is_ok());
+/// ```
+#[derive(Clone, Debug)]
+pub struct FactValidator<'a, F, T>
+where
+    F: Fact<'a, T>,
+    T: Target<'a>,
+{
+    fact: F,
+    _phantom: PhantomData<&'a T>,
+}
+
+impl<'a, F, T> FactValidator<'a, F, T>
+where
+    F: Fact<'a, T>,
+    T: Target<'a>,
+{
+    /// Start validating with a fact in its current state
+    pub fn new(fact: F) -> Self {
+        Self {
+            fact,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Check the next value, advancing the state of the fact if it passes
+    pub fn push(&mut self, t: &T) -> Check {
+        let before = self.fact.save_state();
+        let mut g = Generator::checker();
+        let result = self.fact.mutate(&mut g, t.clone());
+        let check = g.into_check(result);
+        if !check.is_ok() {
+            self.fact.restore_state(&before);
+        }
+        check
+    }
+
+    /// The fact, with the state it has reached so far
+    pub fn fact(&self) -> &F {
+        &self.fact
+    }
+
+    /// Stop validating, returning the fact
+    pub fn into_inner(self) -> F {
+        self.fact
+    }
+
+    /// Check each item of a stream as it arrives, yielding each item along with its Check
+    ///
+    /// ```
+    /// use contrafact::{*, facts::*};
+    /// use futures::{executor::block_on, stream, StreamExt};
+    ///
+    /// let v = FactValidator::new(consecutive_int_(1));
+    /// let checked: Vec<(u32, bool)> = block_on(
+    ///     v.validate_stream(stream::iter([1, 2, 2, 3]))
+    ///         .map(|(x, check)| (x, check.is_ok()))
+    ///         .collect(),
+    /// );
+    /// assert_eq!(checked, vec![(1, true), (2, true), (2, false), (3, true)]);
+    /// ```
+    #[cfg(feature = "stream")]
+    pub fn validate_stream<S>(mut self, stream: S) -> impl 'a + futures::Stream<Item = (T, Check)>
+    where
+        S: 'a + futures::Stream<Item = T>,
+    {
+        use futures::StreamExt;
+        stream.map(move |t| {
+            let check = self.push(&t);
+            (t, check)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::facts::*;
+
+    #[test]
+    fn test_validator_only_advances_on_pass() {
+        let fact = vec(consecutive_int_(0u8));
+        let mut v = FactValidator::new(fact);
+
+        assert!(v.push(&vec![0, 1]).is_ok());
+        // Half of this passes, but none of it counts
+        assert_eq!(
+            v.push(&vec![2, 9]).result().unwrap().unwrap_err(),
+            vec!["[1]: consecutive_int".to_string()]
+        );
+        assert!(v.push(&vec![2, 3, 4]).is_ok());
+        assert!(v.push(&vec![5]).is_ok());
+    }
+}
@@ -122,7 +122,7 @@ struct AlphaSigner;
 impl AlphaSigner {
     fn sign(&self, mut alpha: Alpha) -> Sigma {
         Sigma {
-            id2: alpha.id().clone() * 2,
+            id2: *alpha.id() * 2,
             sig: alpha.id().to_string(),
             alpha,
         }
@@ -130,20 +130,20 @@ impl AlphaSigner {
 }
 
 #[allow(unused)]
-fn alpha_fact() -> impl Fact<'static, Alpha> {
+fn alpha_fact<'a>() -> impl Fact<'a, Alpha> {
     facts![lens1("Alpha::id", |a: &mut Alpha| a.id(), id_fact(None))]
 }
 
-fn beta_fact() -> impl Fact<'static, Beta> {
+fn beta_fact<'a>() -> impl Fact<'a, Beta> {
     facts![lens1("Beta::id", |a: &mut Beta| &mut a.id, id_fact(None))]
 }
 
 /// Just a pair of an Alpha with optional Beta.
-/// An intermediate type not used "in production" but useful for writing Fact 'static, against
+/// An intermediate type not used "in production" but useful for writing Facts against
 #[derive(Clone, Debug, PartialEq, Arbitrary)]
 struct Pi(Alpha, Option<Beta>);
 
-fn pi_beta_match() -> impl Fact<'static, Pi> {
+fn pi_beta_match<'a>() -> impl Fact<'a, Pi> {
     facts![brute(
         "Pi alpha has matching beta iff beta is Some",
         |p: &Pi| match p {
@@ -154,7 +154,7 @@ fn pi_beta_match() -> impl Fact<'static, Pi> {
     )]
 }
 
-fn id_fact(id: Option<Id>) -> impl Fact<'static, Id> {
+fn id_fact<'a>(id: Option<Id>) -> impl Fact<'a, Id> {
     let le = brute("< u32::MAX", |id: &Id| *id < Id::MAX / 2);
 
     if let Some(id) = id {
@@ -166,17 +166,19 @@ fn id_fact(id: Option<Id>) -> impl Fact<'static, Id> {
 
 /// - id must be set as specified
 /// - All Ids should match each other. If there is a Beta, its id should match too.
-fn pi_fact(id: Id) -> impl Fact<'static, Pi> {
+fn pi_fact<'a>(id: Id) -> impl Fact<'a, Pi> {
     let alpha_fact = facts![
         lens1("Alpha::id", |a: &mut Alpha| a.id(), id_fact(Some(id))),
         // lens1("Alpha::data", |a: &mut Alpha| a.data(), eq(data)),
     ];
     let beta_fact = lens1("Beta::id", |b: &mut Beta| &mut b.id, id_fact(Some(id)));
-    facts![
+    guard(
         pi_beta_match(),
-        lens1("Pi::alpha", |o: &mut Pi| &mut o.0, alpha_fact),
-        prism("Pi::beta", |o: &mut Pi| o.1.as_mut(), beta_fact),
-    ]
+        facts![
+            lens1("Pi::alpha", |o: &mut Pi| &mut o.0, alpha_fact),
+            prism("Pi::beta", |o: &mut Pi| o.1.as_mut(), beta_fact),
+        ],
+    )
 }
 
 /// - All Ids should match each other. If there is a Beta, its id should match too
@@ -184,7 +186,7 @@ fn pi_fact(id: Id) -> impl Fact<'static, Pi> {
 /// - If Omega::AlphaBeta, then Alpha::Beta,
 ///     - and, the the Betas of the Alpha and the Omega should match.
 /// - all data must be set as specified
-fn omega_fact(id: Id) -> impl Fact<'static, Omega> {
+fn omega_fact<'a>(id: Id) -> impl Fact<'a, Omega> {
     let omega_pi = lens2(
         "Omega -> Pi",
         |o| match o {
@@ -192,7 +194,7 @@ fn omega_fact(id: Id) -> impl Fact<'static, Omega> {
             Omega::Alpha { alpha, .. } => Pi(alpha, None),
         },
         |o, pi| {
-            let id = o.id().clone();
+            let id = *o.id();
             match pi {
                 Pi(alpha, Some(beta)) => Omega::AlphaBeta { id, alpha, beta },
                 Pi(alpha, None) => Omega::Alpha { id, alpha },
@@ -208,7 +210,7 @@ fn omega_fact(id: Id) -> impl Fact<'static, Omega> {
 }
 
 #[allow(unused)]
-fn sigma_fact() -> impl Fact<'static, Sigma> {
+fn sigma_fact<'a>() -> impl Fact<'a, Sigma> {
     let id2_fact = lens2(
         "Sigma::id is correct",
         |mut s: Sigma| (s.id2, *(s.alpha.id()) * 2),
@@ -235,7 +237,7 @@ fn sigma_fact() -> impl Fact<'static, Sigma> {
 
 /// The inner Sigma is correct wrt to signature
 /// XXX: this is a little wonky, probably room for improvement.
-fn rho_fact(id: Id, signer: AlphaSigner) -> impl Fact<'static, Rho> {
+fn rho_fact<'a>(id: Id, signer: AlphaSigner) -> impl Fact<'a, Rho> {
     let rho_pi = lens2(
         "Rho -> Pi",
         |rho: Rho| Pi(rho.sigma.alpha, rho.beta),
@@ -281,6 +283,21 @@ fn test_rho_fact() {
     dbg!(rho);
 }
 
+#[test]
+fn test_rho_fact_description() {
+    let description = rho_fact(5, AlphaSigner).describe();
+    println!("{}", description);
+
+    assert_eq!(description.kind, "and");
+    let labels: Vec<_> = description.children.iter().map(|d| d.label.as_str()).collect();
+    assert_eq!(labels, vec!["Rho -> Sigma", "Rho -> Pi"]);
+
+    let text = description.to_text();
+    assert!(text.contains("guard"));
+    assert!(text.contains("prism: Pi::beta"));
+    assert!(description.to_dot().starts_with("digraph fact {"));
+}
+
 #[test]
 fn test_omega_fact() {
     observability::test_run().ok();
@@ -332,30 +349,46 @@ fn test_omega_fact() {
     };
 
     // Ensure that check fails for invalid data
-    assert!(
-        dbg!(fact
-            .clone()
-            .check(dbg!(&invalid1))
-            .result()
-            .unwrap()
-            .unwrap_err())
-        .len()
-            > 0
-    );
+    assert!(!dbg!(fact
+        .clone()
+        .check(dbg!(&invalid1))
+        .result()
+        .unwrap()
+        .unwrap_err())
+    .is_empty());
     invalid1 = fact.mutate(&mut g, invalid1).unwrap();
     fact.clone().check(dbg!(&invalid1)).unwrap();
 
     // Ensure that check fails for invalid data
-    assert!(
-        dbg!(fact
-            .clone()
-            .check(dbg!(&invalid2))
-            .result()
-            .unwrap()
-            .unwrap_err())
-        .len()
-            > 0
-    );
+    assert!(!dbg!(fact
+        .clone()
+        .check(dbg!(&invalid2))
+        .result()
+        .unwrap()
+        .unwrap_err())
+    .is_empty());
     invalid2 = fact.mutate(&mut g, invalid2).unwrap();
     fact.clone().check(dbg!(&invalid2)).unwrap();
 }
+
+/// Some long-lived struct which holds onto a Fact
+struct Fixture<F> {
+    fact: F,
+}
+
+#[test]
+fn test_fact_outlives_generator() {
+    observability::test_run().ok();
+
+    let fixture = Fixture {
+        fact: rho_fact(5, AlphaSigner),
+    };
+
+    // Each generator is backed by a buffer which lives shorter than the fact
+    for seed in 0..3u8 {
+        let bytes: Vec<u8> = utils::NOISE.iter().map(|b| b ^ seed).collect();
+        let mut g = Generator::from(&bytes[..]);
+        let rho = fixture.fact.clone().build(&mut g);
+        fixture.fact.clone().check(&rho).unwrap();
+    }
+}